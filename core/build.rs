@@ -0,0 +1,197 @@
+//! Generates the palette tables: each `palettes/<name>.csv` becomes
+//! `$OUT_DIR/palette_<name>.rs` plus a search index for `kdtree`, with
+//! collisions reported as warnings and malformed rows failing the build.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Shared with the library so collision checks and the search index use
+// the same delta E
+#[allow(dead_code)]
+#[path = "src/color_space.rs"]
+mod color_space;
+#[allow(dead_code)]
+#[path = "src/kdtree.rs"]
+mod kdtree;
+#[allow(dead_code)]
+#[path = "src/lab.rs"]
+mod lab;
+
+use color_space::Lab;
+use kdtree::Node;
+use lab::DeltaE;
+
+/// Entries closer than this (CIEDE2000) are reported as near collisions;
+/// about one just-noticeable difference.
+const NEAR_COLLISION: f32 = 1.0;
+
+fn main() {
+    let out = &PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    generate_palettes(out);
+}
+
+struct Row {
+    line: usize,
+    name: String,
+    code: Option<String>,
+    rgb: [u8; 3],
+    lab: Lab,
+}
+
+/// Turn each `palettes/<name>.csv` into `$OUT_DIR/palette_<name>.rs`,
+/// included by the matching `src/palette/<name>.rs`.
+fn generate_palettes(out: &Path) {
+    println!("cargo:rerun-if-changed=palettes");
+    for src in ["src/color_space.rs", "src/kdtree.rs", "src/lab.rs"] {
+        println!("cargo:rerun-if-changed={src}");
+    }
+    let mut paths: Vec<PathBuf> = fs::read_dir("palettes")
+        .expect("palettes/ directory")
+        .map(|e| e.unwrap().path())
+        .filter(|p| p.extension().is_some_and(|e| e == "csv"))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let stem = path.file_stem().unwrap().to_str().unwrap();
+        let rows = parse_palette(&path);
+        check_collisions(&path, &rows);
+        if rows.len() > u16::MAX as usize {
+            panic!("{}: too many entries", path.display());
+        }
+        let nodes = build_index(&rows);
+
+        // f32 Debug output round-trips exactly, so the firmware sees the
+        // same Lab values the index was built from
+        let mut code = String::from("const ENTRIES: &[Entry] = &[\n");
+        for row in &rows {
+            let [r, g, b] = row.rgb;
+            let lab = &row.lab;
+            code += &format!(
+                "    Entry {{ name: {:?}, code: {:?}, color: embedded_graphics::pixelcolor::Rgb888::new({}, {}, {}), lab: crate::color_space::Lab {{ l: {:?}, a: {:?}, b: {:?} }} }},\n",
+                row.name, row.code, r, g, b, lab.l, lab.a, lab.b
+            );
+        }
+        code += "];\n\nconst INDEX: &[crate::kdtree::Node] = &[\n";
+        for n in &nodes {
+            code += &format!(
+                "    crate::kdtree::Node {{ entry: {}, axis: {}, min: {:?}, max: {:?}, max_chroma: {:?} }},\n",
+                n.entry, n.axis, n.min, n.max, n.max_chroma
+            );
+        }
+        code += "];\n";
+        fs::write(out.join(format!("palette_{stem}.rs")), code).unwrap();
+    }
+}
+
+/// `name,code,hex` with a header line; `#` starts a comment line and the
+/// code may be empty. Malformed lines fail the build.
+fn parse_palette(path: &Path) -> Vec<Row> {
+    let text = fs::read_to_string(path).unwrap();
+    let mut rows = Vec::new();
+    let mut header = false;
+    for (i, line) in text.lines().enumerate() {
+        let line_no = i + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if !header {
+            if line != "name,code,hex" {
+                panic!(
+                    "{}:{}: expected header `name,code,hex`",
+                    path.display(),
+                    line_no
+                );
+            }
+            header = true;
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let [name, code, hex] = fields[..] else {
+            panic!(
+                "{}:{}: expected 3 fields, found {}",
+                path.display(),
+                line_no,
+                fields.len()
+            );
+        };
+        if name.is_empty() {
+            panic!("{}:{}: empty name", path.display(), line_no);
+        }
+        let Some(rgb) = parse_hex(hex) else {
+            panic!("{}:{}: malformed hex `{}`", path.display(), line_no, hex);
+        };
+        rows.push(Row {
+            line: line_no,
+            name: name.to_string(),
+            code: (!code.is_empty()).then(|| code.to_string()),
+            rgb,
+            lab: Lab::from_srgb8(rgb[0], rgb[1], rgb[2]),
+        });
+    }
+    rows
+}
+
+/// `#RRGGBB`, case-insensitive.
+fn parse_hex(hex: &str) -> Option<[u8; 3]> {
+    let digits = hex.strip_prefix('#')?;
+    if digits.len() != 6 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let v = u32::from_str_radix(digits, 16).ok()?;
+    Some([(v >> 16) as u8, (v >> 8) as u8, v as u8])
+}
+
+/// Warn about entries the matcher can't tell apart: identical values make
+/// the result depend on table order, and near-identical ones flicker.
+fn check_collisions(path: &Path, rows: &[Row]) {
+    for (i, a) in rows.iter().enumerate() {
+        for b in &rows[i + 1..] {
+            if a.rgb == b.rgb {
+                println!(
+                    "cargo:warning={}:{}: `{}` has the same color as `{}` (line {})",
+                    path.display(),
+                    b.line,
+                    b.name,
+                    a.name,
+                    a.line
+                );
+                continue;
+            }
+            let distance = DeltaE::Ciede2000.distance(&a.lab, &b.lab);
+            if distance < NEAR_COLLISION {
+                println!(
+                    "cargo:warning={}:{}: `{}` is within dE00 {:.2} of `{}` (line {})",
+                    path.display(),
+                    b.line,
+                    b.name,
+                    distance,
+                    a.name,
+                    a.line
+                );
+            }
+        }
+    }
+}
+
+/// Lay the entries out as the tree `kdtree::nearest` walks.
+fn build_index(rows: &[Row]) -> Vec<Node> {
+    let labs: Vec<Lab> = rows.iter().map(|r| r.lab).collect();
+    let mut order: Vec<u16> = (0..rows.len() as u16).collect();
+    let mut nodes = vec![
+        Node {
+            entry: 0,
+            axis: 0,
+            min: [0.0; 3],
+            max: [0.0; 3],
+            max_chroma: 0.0,
+        };
+        rows.len()
+    ];
+    kdtree::build(&labs, &mut order, &mut nodes);
+    nodes
+}
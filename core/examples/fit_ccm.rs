@@ -0,0 +1,129 @@
+//! Host-side color correction fit from recorded readings.
+//!
+//! Run from `core/`:
+//!
+//!     cargo run --release --example fit_ccm -- [--offset] < readings.csv
+//!
+//! Each line is `r,g,b` reflectance, optionally followed by the reference as
+//! `,#RRGGBB`; lines without a reference are matched to the ColorChecker
+//! patches in chart order. The firmware's chart calibration log lines
+//! (`Patch N: r g b`, with or without the RTT prefix) are read as they are,
+//! with patch N's reference. Blank lines and lines starting with `#` are
+//! skipped.
+
+use std::io::{self, BufRead};
+use std::process::ExitCode;
+
+use colorpicky_core::ccm::{Fit, COLORCHECKER};
+use colorpicky_core::color_space::{decode_srgb8, linear_to_srgb};
+
+/// Linear value to 8-bit sRGB, unrounded so the residuals keep their decimals.
+fn encode(v: f32) -> f32 {
+    linear_to_srgb(v.clamp(0.0, 1.0)) * 255.0
+}
+
+fn parse_hex(s: &str) -> Option<[u8; 3]> {
+    let s = s.trim().trim_start_matches('#');
+    if s.len() != 6 {
+        return None;
+    }
+    let n = u32::from_str_radix(s, 16).ok()?;
+    Some([(n >> 16) as u8, (n >> 8) as u8, n as u8])
+}
+
+/// Exactly three numbers.
+fn parse_measured(fields: &[&str]) -> Option<[f32; 3]> {
+    match fields {
+        [r, g, b] => Some([
+            r.trim().parse().ok()?,
+            g.trim().parse().ok()?,
+            b.trim().parse().ok()?,
+        ]),
+        _ => None,
+    }
+}
+
+/// A firmware log line, `[...] Patch N: r g b`: the patch number and values.
+fn parse_log(line: &str) -> Option<(usize, Vec<&str>)> {
+    let rest = &line[line.find("Patch ")? + "Patch ".len()..];
+    let (patch, values) = rest.split_once(':')?;
+    Some((
+        patch.trim().parse().ok()?,
+        values.split_whitespace().collect(),
+    ))
+}
+
+fn main() -> ExitCode {
+    let offset = std::env::args().any(|a| a == "--offset");
+
+    let mut patches: Vec<([f32; 3], [u8; 3])> = Vec::new();
+    for (lineno, line) in io::stdin().lock().lines().enumerate() {
+        let line = line.expect("failed to read stdin");
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (measured, reference) = match parse_log(line) {
+            Some((patch, fields)) => (
+                parse_measured(&fields),
+                patch
+                    .checked_sub(1)
+                    .and_then(|i| COLORCHECKER.get(i))
+                    .map(|(_, rgb)| *rgb),
+            ),
+            None => {
+                let fields: Vec<&str> = line.split(',').collect();
+                let reference = match fields.get(3) {
+                    Some(hex) => parse_hex(hex),
+                    None => COLORCHECKER.get(patches.len()).map(|(_, rgb)| *rgb),
+                };
+                (parse_measured(&fields[..fields.len().min(3)]), reference)
+            }
+        };
+        match (measured, reference) {
+            (Some(m), Some(reference)) => patches.push((m, reference)),
+            _ => {
+                eprintln!(
+                    "line {}: expected r,g,b[,#RRGGBB] or Patch N: r g b",
+                    lineno + 1
+                );
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let mut fit = Fit::new(offset);
+    for (measured, reference) in &patches {
+        fit.add(*measured, reference.map(decode_srgb8));
+    }
+    let Some(matrix) = fit.solve() else {
+        eprintln!("fit failed: need more (and more varied) patches than unknowns");
+        return ExitCode::FAILURE;
+    };
+
+    println!("{:?}", matrix.m);
+
+    // Residuals in 8-bit sRGB units
+    let mut sum_sq = 0.0;
+    for (i, (measured, reference)) in patches.iter().enumerate() {
+        let out = matrix.apply(*measured).map(encode);
+        let err = (0..3)
+            .map(|k| (out[k] - reference[k] as f32).powi(2))
+            .sum::<f32>()
+            .sqrt();
+        sum_sq += err * err;
+        println!(
+            "{:2}: ref #{:02X}{:02X}{:02X} -> {:5.1} {:5.1} {:5.1}  err {:.1}",
+            i + 1,
+            reference[0],
+            reference[1],
+            reference[2],
+            out[0],
+            out[1],
+            out[2],
+            err
+        );
+    }
+    println!("RMS error: {:.2}", (sum_sq / patches.len() as f32).sqrt());
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,142 @@
+//! Chromatic adaptation between illuminants.
+//!
+//! A white sheet under a tungsten bulb reads orange, but people still see it
+//! as white. Adapting a reading from the light it was taken under to D65
+//! (the sRGB white) before matching or display gives the color a person in
+//! that room would name. Both transforms scale cone-like responses by the
+//! ratio of the two whites (von Kries, full adaptation); they differ in the
+//! cone space.
+
+use crate::color_space::{von_kries, LinearRgb, Matrix3, Xyz, BRADFORD, BRADFORD_INV, D50, D65};
+
+/// Chromatic adaptation transform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Cat {
+    /// The ICC and Lindbloom standard; what `color_space` uses for D50.
+    #[default]
+    Bradford,
+    /// From CAM16; slightly better on the corresponding-colors data sets.
+    Cat16,
+}
+
+const CAT16: Matrix3 = [
+    [0.401_288, 0.650_173, -0.051_461],
+    [-0.250_268, 1.204_414, 0.045_854],
+    [-0.002_079, 0.048_952, 0.953_127],
+];
+
+const CAT16_INV: Matrix3 = [
+    [1.862_067_9, -1.011_254_6, 0.149_186_8],
+    [0.387_526_5, 0.621_447_4, -0.008_974],
+    [-0.015_841_5, -0.034_122_9, 1.049_964_4],
+];
+
+impl Cat {
+    pub fn label(self) -> &'static str {
+        match self {
+            Cat::Bradford => "Bradford",
+            Cat::Cat16 => "CAT16",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Cat::Bradford => Cat::Cat16,
+            Cat::Cat16 => Cat::Bradford,
+        }
+    }
+
+    fn matrices(self) -> (&'static Matrix3, &'static Matrix3) {
+        match self {
+            Cat::Bradford => (&BRADFORD, &BRADFORD_INV),
+            Cat::Cat16 => (&CAT16, &CAT16_INV),
+        }
+    }
+
+    /// Adapt `xyz`, seen under white `from`, to how it looks under `to`.
+    /// Whites are XYZ with Y = 1.
+    pub fn adapt(self, xyz: Xyz, from: Xyz, to: Xyz) -> Xyz {
+        let (m, m_inv) = self.matrices();
+        von_kries(m, m_inv, xyz, from, to)
+    }
+}
+
+/// Light a reading was taken under. White points are CIE 1931 2°, Y = 1.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Illuminant {
+    /// Incandescent / tungsten, 2856 K
+    A,
+    /// Horizon daylight, 5003 K; graphic arts viewing booths
+    D50,
+    /// Noon daylight, 6504 K; the sRGB white, so no adaptation
+    #[default]
+    D65,
+    /// Cool white fluorescent, 4230 K
+    F2,
+    /// Broadband daylight fluorescent, 6500 K
+    F7,
+    /// Narrow-band (tri-phosphor) fluorescent, 4000 K; common in shops
+    F11,
+    /// White point measured from the light itself
+    Measured(Xyz),
+}
+
+impl Illuminant {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Illuminant::A => "A",
+            Illuminant::D50 => "D50",
+            Illuminant::D65 => "D65",
+            Illuminant::F2 => "F2",
+            Illuminant::F7 => "F7",
+            Illuminant::F11 => "F11",
+            Illuminant::Measured(_) => "Measured",
+        }
+    }
+
+    pub fn white(&self) -> Xyz {
+        match self {
+            Illuminant::A => Xyz::new(1.098_50, 1.0, 0.355_85),
+            Illuminant::D50 => D50,
+            Illuminant::D65 => D65,
+            Illuminant::F2 => Xyz::new(0.991_86, 1.0, 0.673_93),
+            Illuminant::F7 => Xyz::new(0.950_41, 1.0, 1.087_47),
+            Illuminant::F11 => Xyz::new(1.009_62, 1.0, 0.643_50),
+            Illuminant::Measured(white) => *white,
+        }
+    }
+
+    /// Next standard illuminant; a measured white is left by cycling past it.
+    pub fn next(&self) -> Self {
+        match self {
+            Illuminant::A => Illuminant::D50,
+            Illuminant::D50 => Illuminant::D65,
+            Illuminant::D65 => Illuminant::F2,
+            Illuminant::F2 => Illuminant::F7,
+            Illuminant::F7 => Illuminant::F11,
+            Illuminant::F11 | Illuminant::Measured(_) => Illuminant::A,
+        }
+    }
+
+    /// White point from linear RGB seen looking at the light (or a white
+    /// surface under it). `None` for black.
+    pub fn measured(rgb: LinearRgb) -> Option<Self> {
+        let xyz = rgb.to_xyz();
+        if xyz.y <= 0.0 {
+            return None;
+        }
+        Some(Illuminant::Measured(Xyz::new(
+            xyz.x / xyz.y,
+            1.0,
+            xyz.z / xyz.y,
+        )))
+    }
+
+    /// Adapt linear sRGB taken under this light to D65.
+    pub fn to_d65(&self, rgb: LinearRgb, cat: Cat) -> LinearRgb {
+        if *self == Illuminant::D65 {
+            return rgb;
+        }
+        cat.adapt(rgb.to_xyz(), self.white(), D65).to_linear_rgb()
+    }
+}
@@ -0,0 +1,102 @@
+//! White/black reference calibration.
+//!
+//! `Reading::rgb8()` divides by the clear channel, which keeps chromaticity
+//! but throws lightness away: dark brown and tan come out the same. With a
+//! black and a white reference tile measured, each channel maps to a
+//! reflectance between the two, so a dark sample stays dark.
+
+use crate::sensor::{ExposureStatus, Reading};
+
+/// Tag at the start of a stored calibration; bump the digit if the layout changes.
+const MAGIC: [u8; 4] = *b"CPK1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationError {
+    /// The white reference clipped; lower the light or move the tile.
+    WhiteSaturated,
+    /// A channel reads no higher on white than on black.
+    NoContrast,
+    /// Stored bytes are not a calibration written by `to_bytes()`.
+    Corrupt,
+}
+
+impl CalibrationError {
+    pub fn label(self) -> &'static str {
+        match self {
+            CalibrationError::WhiteSaturated => "White too bright",
+            CalibrationError::NoContrast => "White not brighter",
+            CalibrationError::Corrupt => "Bad calibration",
+        }
+    }
+}
+
+/// Black and white references as normalized `[r, g, b, c]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Calibration {
+    black: [f32; 4],
+    white: [f32; 4],
+}
+
+impl Calibration {
+    /// Size of `to_bytes()` output.
+    pub const SIZE: usize = 4 + 8 * 4;
+
+    /// Build a calibration from readings of the black and white tiles.
+    /// The black tile may be underexposed; the white one must not clip.
+    pub fn new(black: &Reading, white: &Reading) -> Result<Self, CalibrationError> {
+        if white.meta.status == ExposureStatus::Saturated {
+            return Err(CalibrationError::WhiteSaturated);
+        }
+        Self::from_references(black.normalized(), white.normalized())
+    }
+
+    fn from_references(black: [f32; 4], white: [f32; 4]) -> Result<Self, CalibrationError> {
+        if (0..4).any(|i| white[i] <= black[i]) {
+            return Err(CalibrationError::NoContrast);
+        }
+        Ok(Self { black, white })
+    }
+
+    /// Per-channel reflectance `[r, g, b]`: 0 at the black tile, 1 at the
+    /// white tile. Not clamped, so fluorescent samples can exceed 1.
+    pub fn reflectance(&self, reading: &Reading) -> [f32; 3] {
+        let n = reading.normalized();
+        let mut out = [0.0; 3];
+        for (i, v) in out.iter_mut().enumerate() {
+            *v = ((n[i] - self.black[i]) / (self.white[i] - self.black[i])).max(0.0);
+        }
+        out
+    }
+
+    /// Serialize for storage (e.g. a flash sector).
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut out = [0u8; Self::SIZE];
+        out[..4].copy_from_slice(&MAGIC);
+        for (i, v) in self.black.iter().chain(self.white.iter()).enumerate() {
+            out[4 + i * 4..8 + i * 4].copy_from_slice(&v.to_le_bytes());
+        }
+        out
+    }
+
+    /// Load a calibration written by `to_bytes()`.
+    pub fn from_bytes(bytes: &[u8; Self::SIZE]) -> Result<Self, CalibrationError> {
+        if bytes[..4] != MAGIC {
+            return Err(CalibrationError::Corrupt);
+        }
+        let mut values = [0.0f32; 8];
+        for (i, v) in values.iter_mut().enumerate() {
+            let mut word = [0u8; 4];
+            word.copy_from_slice(&bytes[4 + i * 4..8 + i * 4]);
+            *v = f32::from_le_bytes(word);
+            if !v.is_finite() {
+                return Err(CalibrationError::Corrupt);
+            }
+        }
+        let (black, white) = values.split_at(4);
+        Self::from_references(
+            black.try_into().map_err(|_| CalibrationError::Corrupt)?,
+            white.try_into().map_err(|_| CalibrationError::Corrupt)?,
+        )
+        .map_err(|_| CalibrationError::Corrupt)
+    }
+}
@@ -0,0 +1,179 @@
+//! Color correction matrix fitted from a reference chart.
+//!
+//! The TCS34725 filters do not match the sRGB primaries, so even after tile
+//! calibration blues and purples drift. Measuring patches with known values
+//! and fitting a 3x3 (or 3x4 with an offset column) matrix by least squares
+//! maps sensor reflectance onto linear sRGB.
+//!
+//! The host example `examples/fit_ccm.rs` uses the same fit to refit from
+//! recorded readings.
+
+/// X-Rite ColorChecker Classic patches as 8-bit sRGB, in chart order.
+pub const COLORCHECKER: [(&str, [u8; 3]); 24] = [
+    ("Dark skin", [115, 82, 68]),
+    ("Light skin", [194, 150, 130]),
+    ("Blue sky", [98, 122, 157]),
+    ("Foliage", [87, 108, 67]),
+    ("Blue flower", [133, 128, 177]),
+    ("Bluish green", [103, 189, 170]),
+    ("Orange", [214, 126, 44]),
+    ("Purplish blue", [80, 91, 166]),
+    ("Moderate red", [193, 90, 99]),
+    ("Purple", [94, 60, 108]),
+    ("Yellow green", [157, 188, 64]),
+    ("Orange yellow", [224, 163, 46]),
+    ("Blue", [56, 61, 150]),
+    ("Green", [70, 148, 73]),
+    ("Red", [175, 54, 60]),
+    ("Yellow", [231, 199, 31]),
+    ("Magenta", [187, 86, 149]),
+    ("Cyan", [8, 133, 161]),
+    ("White", [243, 243, 242]),
+    ("Neutral 8", [200, 200, 200]),
+    ("Neutral 6.5", [160, 160, 160]),
+    ("Neutral 5", [122, 122, 121]),
+    ("Neutral 3.5", [85, 85, 85]),
+    ("Black", [52, 52, 52]),
+];
+
+/// Maps linear `[r, g, b]` through `out = m * [r, g, b, 1]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix {
+    /// One row per output channel; the last column is the offset.
+    pub m: [[f32; 4]; 3],
+}
+
+impl Matrix {
+    pub const IDENTITY: Matrix = Matrix {
+        m: [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+        ],
+    };
+
+    pub fn apply(&self, rgb: [f32; 3]) -> [f32; 3] {
+        self.m
+            .map(|row| row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2] + row[3])
+    }
+}
+
+impl Default for Matrix {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+/// Least-squares accumulator. Patches are added one at a time so the device
+/// never has to hold the whole chart.
+#[derive(Debug, Clone, Copy)]
+pub struct Fit {
+    /// Normal equations `AᵀA x = Aᵀy`, accumulated in f64 for stability.
+    ata: [[f64; 4]; 4],
+    aty: [[f64; 3]; 4],
+    count: usize,
+    offset: bool,
+}
+
+impl Fit {
+    /// Start a fit; `offset` adds a constant column (3x4) for sensor dark
+    /// level or stray light that tile calibration did not remove.
+    pub fn new(offset: bool) -> Self {
+        Self {
+            ata: [[0.0; 4]; 4],
+            aty: [[0.0; 3]; 4],
+            count: 0,
+            offset,
+        }
+    }
+
+    /// Add one patch: measured linear values and their linear reference.
+    pub fn add(&mut self, measured: [f32; 3], reference: [f32; 3]) {
+        let x = [
+            measured[0] as f64,
+            measured[1] as f64,
+            measured[2] as f64,
+            1.0,
+        ];
+        for (i, (ata, aty)) in self.ata.iter_mut().zip(&mut self.aty).enumerate() {
+            for (dst, xj) in ata.iter_mut().zip(x) {
+                *dst += x[i] * xj;
+            }
+            for (dst, y) in aty.iter_mut().zip(reference) {
+                *dst += x[i] * y as f64;
+            }
+        }
+        self.count += 1;
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    fn unknowns(&self) -> usize {
+        if self.offset {
+            4
+        } else {
+            3
+        }
+    }
+
+    /// Solve for the matrix. `None` with fewer patches than unknowns or when
+    /// the patches don't span the color space (e.g. only grays).
+    #[allow(clippy::needless_range_loop)]
+    pub fn solve(&self) -> Option<Matrix> {
+        let n = self.unknowns();
+        if self.count < n {
+            return None;
+        }
+
+        let mut a = self.ata;
+        let mut b = self.aty;
+
+        // Gaussian elimination with partial pivoting on the n x n system
+        for col in 0..n {
+            let pivot = (col..n).max_by(|&i, &j| {
+                a[i][col]
+                    .abs()
+                    .partial_cmp(&a[j][col].abs())
+                    .unwrap_or(core::cmp::Ordering::Equal)
+            })?;
+            if a[pivot][col].abs() < 1e-12 {
+                return None;
+            }
+            a.swap(col, pivot);
+            b.swap(col, pivot);
+
+            for row in col + 1..n {
+                let factor = a[row][col] / a[col][col];
+                for k in col..n {
+                    a[row][k] -= factor * a[col][k];
+                }
+                for k in 0..3 {
+                    b[row][k] -= factor * b[col][k];
+                }
+            }
+        }
+
+        // Back substitution, one output channel at a time
+        let mut matrix = Matrix { m: [[0.0; 4]; 3] };
+        for out in 0..3 {
+            let mut x = [0.0f64; 4];
+            for row in (0..n).rev() {
+                let mut sum = b[row][out];
+                for k in row + 1..n {
+                    sum -= a[row][k] * x[k];
+                }
+                x[row] = sum / a[row][row];
+            }
+            for (dst, v) in matrix.m[out].iter_mut().zip(x) {
+                *dst = v as f32;
+            }
+        }
+        Some(matrix)
+    }
+}
@@ -0,0 +1,528 @@
+//! Color space conversions.
+//!
+//! One place for every representation the app needs, so matching, the
+//! harmonies and the on-screen readouts agree on the numbers. Everything is
+//! `f32` with sRGB components in 0..1, hues in degrees, and no clamping on
+//! the way through, so out-of-gamut values survive a round trip.
+//!
+//! ```text
+//! Srgb <-> LinearRgb <-> Xyz (D65) <-> Lab <-> Lch
+//!   |          |          `-> D50
+//!   |          `-> Oklab <-> Oklch
+//!   `-> Hsv / Hsl / Cmyk
+//! ```
+
+use libm::{atan2f, cbrtf, cosf, powf, sinf, sqrtf};
+
+/// Gamma-encoded sRGB, 0..1.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Srgb {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+}
+
+/// Linear-light sRGB primaries, 0..1 inside the gamut.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct LinearRgb {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+}
+
+/// CIE 1931 XYZ with Y = 1 for the reference white.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Xyz {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+/// CIELAB relative to a reference white (D65 unless stated).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Lab {
+    pub l: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+/// CIELAB in polar form.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Lch {
+    pub l: f32,
+    pub c: f32,
+    pub h: f32,
+}
+
+/// Björn Ottosson's OKLab; `l` is 0..1.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Oklab {
+    pub l: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+/// OKLab in polar form.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Oklch {
+    pub l: f32,
+    pub c: f32,
+    pub h: f32,
+}
+
+/// Hue in degrees, saturation and value 0..1.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Hsv {
+    pub h: f32,
+    pub s: f32,
+    pub v: f32,
+}
+
+/// Hue in degrees, saturation and lightness 0..1.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Hsl {
+    pub h: f32,
+    pub s: f32,
+    pub l: f32,
+}
+
+/// Naive device-independent CMYK (no ink profile), 0..1.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Cmyk {
+    pub c: f32,
+    pub m: f32,
+    pub y: f32,
+    pub k: f32,
+}
+
+/// CIE standard illuminant D65 (sRGB white).
+pub const D65: Xyz = Xyz {
+    x: 0.950_47,
+    y: 1.0,
+    z: 1.088_83,
+};
+
+/// CIE standard illuminant D50 (ICC profile connection space).
+pub const D50: Xyz = Xyz {
+    x: 0.964_22,
+    y: 1.0,
+    z: 0.825_21,
+};
+
+pub(crate) type Matrix3 = [[f32; 3]; 3];
+
+const SRGB_TO_XYZ: Matrix3 = [
+    [0.412_456_4, 0.357_576_1, 0.180_437_5],
+    [0.212_672_9, 0.715_152_2, 0.072_175],
+    [0.019_333_9, 0.119_192, 0.950_304_1],
+];
+
+const XYZ_TO_SRGB: Matrix3 = [
+    [3.240_454_2, -1.537_138_5, -0.498_531_4],
+    [-0.969_266, 1.876_010_8, 0.041_556],
+    [0.055_643_4, -0.204_025_9, 1.057_225_2],
+];
+
+/// Bradford cone response matrix and its inverse, shared with `adaptation`.
+pub(crate) const BRADFORD: Matrix3 = [
+    [0.8951, 0.2664, -0.1614],
+    [-0.7502, 1.7135, 0.0367],
+    [0.0389, -0.0685, 1.0296],
+];
+
+pub(crate) const BRADFORD_INV: Matrix3 = [
+    [0.986_992_9, -0.147_054_3, 0.159_962_7],
+    [0.432_305_3, 0.518_360_3, 0.049_291_2],
+    [-0.008_528_7, 0.040_042_8, 0.968_486_7],
+];
+
+pub(crate) fn mul(m: &Matrix3, v: [f32; 3]) -> [f32; 3] {
+    m.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+}
+
+/// Von Kries adaptation of `xyz` from white `from` to white `to`, scaling
+/// the cone responses of `m` (with inverse `m_inv`) by the ratio of the whites.
+pub(crate) fn von_kries(m: &Matrix3, m_inv: &Matrix3, xyz: Xyz, from: Xyz, to: Xyz) -> Xyz {
+    let src = mul(m, [from.x, from.y, from.z]);
+    let dst = mul(m, [to.x, to.y, to.z]);
+    let mut lms = mul(m, [xyz.x, xyz.y, xyz.z]);
+    for ((v, s), d) in lms.iter_mut().zip(src).zip(dst) {
+        *v *= d / s;
+    }
+    let [x, y, z] = mul(m_inv, lms);
+    Xyz { x, y, z }
+}
+
+/// sRGB transfer function, encoded to linear. Odd-extended for negatives.
+pub fn srgb_to_linear(v: f32) -> f32 {
+    let a = v.abs();
+    let l = if a <= 0.040_45 {
+        a / 12.92
+    } else {
+        powf((a + 0.055) / 1.055, 2.4)
+    };
+    if v < 0.0 {
+        -l
+    } else {
+        l
+    }
+}
+
+/// sRGB transfer function, linear to encoded. Odd-extended for negatives.
+pub fn linear_to_srgb(v: f32) -> f32 {
+    let a = v.abs();
+    let e = if a <= 0.003_130_8 {
+        a * 12.92
+    } else {
+        1.055 * powf(a, 1.0 / 2.4) - 0.055
+    };
+    if v < 0.0 {
+        -e
+    } else {
+        e
+    }
+}
+
+/// 8-bit sRGB value to linear 0..1.
+pub fn decode_srgb8(v: u8) -> f32 {
+    srgb_to_linear(v as f32 / 255.0)
+}
+
+/// Linear value to 8-bit sRGB, clamped to the gamut.
+pub fn encode_srgb8(v: f32) -> u8 {
+    to_u8(linear_to_srgb(v.clamp(0.0, 1.0)))
+}
+
+fn to_u8(v: f32) -> u8 {
+    (v.clamp(0.0, 1.0) * 255.0 + 0.5) as u8
+}
+
+/// Normalize a hue to 0..360.
+fn wrap_hue(h: f32) -> f32 {
+    let h = h % 360.0;
+    if h < 0.0 {
+        h + 360.0
+    } else {
+        h
+    }
+}
+
+fn polar(a: f32, b: f32) -> (f32, f32) {
+    let c = sqrtf(a * a + b * b);
+    let h = if c == 0.0 {
+        0.0
+    } else {
+        wrap_hue(atan2f(b, a).to_degrees())
+    };
+    (c, h)
+}
+
+fn cartesian(c: f32, h: f32) -> (f32, f32) {
+    let h = h.to_radians();
+    (c * cosf(h), c * sinf(h))
+}
+
+impl Srgb {
+    pub const fn new(r: f32, g: f32, b: f32) -> Self {
+        Self { r, g, b }
+    }
+
+    pub fn from_rgb8(r: u8, g: u8, b: u8) -> Self {
+        Self::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0)
+    }
+
+    /// Rounded and clamped to the gamut.
+    pub fn to_rgb8(self) -> (u8, u8, u8) {
+        (to_u8(self.r), to_u8(self.g), to_u8(self.b))
+    }
+
+    pub fn to_linear(self) -> LinearRgb {
+        LinearRgb::new(
+            srgb_to_linear(self.r),
+            srgb_to_linear(self.g),
+            srgb_to_linear(self.b),
+        )
+    }
+
+    fn max_min(self) -> (f32, f32) {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        (max, min)
+    }
+
+    /// Hue of the HSV/HSL hexcone, 0 for grays.
+    fn hexcone_hue(self, max: f32, delta: f32) -> f32 {
+        if delta == 0.0 {
+            0.0
+        } else if max == self.r {
+            wrap_hue(60.0 * ((self.g - self.b) / delta))
+        } else if max == self.g {
+            60.0 * ((self.b - self.r) / delta + 2.0)
+        } else {
+            60.0 * ((self.r - self.g) / delta + 4.0)
+        }
+    }
+
+    pub fn to_hsv(self) -> Hsv {
+        let (max, min) = self.max_min();
+        let delta = max - min;
+        Hsv {
+            h: self.hexcone_hue(max, delta),
+            s: if max == 0.0 { 0.0 } else { delta / max },
+            v: max,
+        }
+    }
+
+    pub fn to_hsl(self) -> Hsl {
+        let (max, min) = self.max_min();
+        let delta = max - min;
+        let l = (max + min) / 2.0;
+        let s = if delta == 0.0 {
+            0.0
+        } else {
+            delta / (1.0 - (2.0 * l - 1.0).abs())
+        };
+        Hsl {
+            h: self.hexcone_hue(max, delta),
+            s,
+            l,
+        }
+    }
+
+    pub fn to_cmyk(self) -> Cmyk {
+        let k = 1.0 - self.r.max(self.g).max(self.b);
+        if k >= 1.0 {
+            return Cmyk {
+                c: 0.0,
+                m: 0.0,
+                y: 0.0,
+                k: 1.0,
+            };
+        }
+        Cmyk {
+            c: (1.0 - self.r - k) / (1.0 - k),
+            m: (1.0 - self.g - k) / (1.0 - k),
+            y: (1.0 - self.b - k) / (1.0 - k),
+            k,
+        }
+    }
+
+    pub fn to_xyz(self) -> Xyz {
+        self.to_linear().to_xyz()
+    }
+
+    pub fn to_lab(self) -> Lab {
+        self.to_xyz().to_lab(D65)
+    }
+
+    pub fn to_oklab(self) -> Oklab {
+        self.to_linear().to_oklab()
+    }
+}
+
+impl LinearRgb {
+    pub const fn new(r: f32, g: f32, b: f32) -> Self {
+        Self { r, g, b }
+    }
+
+    pub fn to_srgb(self) -> Srgb {
+        Srgb::new(
+            linear_to_srgb(self.r),
+            linear_to_srgb(self.g),
+            linear_to_srgb(self.b),
+        )
+    }
+
+    /// XYZ relative to D65.
+    pub fn to_xyz(self) -> Xyz {
+        let [x, y, z] = mul(&SRGB_TO_XYZ, [self.r, self.g, self.b]);
+        Xyz { x, y, z }
+    }
+
+    pub fn to_oklab(self) -> Oklab {
+        let l = 0.412_221_46 * self.r + 0.536_332_55 * self.g + 0.051_445_995 * self.b;
+        let m = 0.211_903_5 * self.r + 0.680_699_5 * self.g + 0.107_396_96 * self.b;
+        let s = 0.088_302_46 * self.r + 0.281_718_85 * self.g + 0.629_978_7 * self.b;
+        let (l, m, s) = (cbrtf(l), cbrtf(m), cbrtf(s));
+        Oklab {
+            l: 0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            a: 1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            b: 0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        }
+    }
+}
+
+impl Xyz {
+    pub const fn new(x: f32, y: f32, z: f32) -> Self {
+        Self { x, y, z }
+    }
+
+    /// From D65 XYZ to linear sRGB.
+    pub fn to_linear_rgb(self) -> LinearRgb {
+        let [r, g, b] = mul(&XYZ_TO_SRGB, [self.x, self.y, self.z]);
+        LinearRgb::new(r, g, b)
+    }
+
+    pub fn to_srgb(self) -> Srgb {
+        self.to_linear_rgb().to_srgb()
+    }
+
+    /// Adapt D65 XYZ to D50 (Bradford).
+    pub fn d65_to_d50(self) -> Xyz {
+        von_kries(&BRADFORD, &BRADFORD_INV, self, D65, D50)
+    }
+
+    /// CIELAB relative to `white` (use `D50` with D50-adapted XYZ).
+    pub fn to_lab(self, white: Xyz) -> Lab {
+        let fx = lab_f(self.x / white.x);
+        let fy = lab_f(self.y / white.y);
+        let fz = lab_f(self.z / white.z);
+        Lab {
+            l: 116.0 * fy - 16.0,
+            a: 500.0 * (fx - fy),
+            b: 200.0 * (fy - fz),
+        }
+    }
+}
+
+const LAB_DELTA: f32 = 6.0 / 29.0;
+
+fn lab_f(t: f32) -> f32 {
+    if t > LAB_DELTA * LAB_DELTA * LAB_DELTA {
+        cbrtf(t)
+    } else {
+        t / (3.0 * LAB_DELTA * LAB_DELTA) + 4.0 / 29.0
+    }
+}
+
+fn lab_f_inv(t: f32) -> f32 {
+    if t > LAB_DELTA {
+        t * t * t
+    } else {
+        3.0 * LAB_DELTA * LAB_DELTA * (t - 4.0 / 29.0)
+    }
+}
+
+impl Lab {
+    pub const fn new(l: f32, a: f32, b: f32) -> Self {
+        Self { l, a, b }
+    }
+
+    /// 8-bit sRGB to CIELAB (D65).
+    pub fn from_srgb8(r: u8, g: u8, b: u8) -> Self {
+        Srgb::from_rgb8(r, g, b).to_lab()
+    }
+
+    pub fn chroma(&self) -> f32 {
+        sqrtf(self.a * self.a + self.b * self.b)
+    }
+
+    pub fn to_xyz(self, white: Xyz) -> Xyz {
+        let fy = (self.l + 16.0) / 116.0;
+        let fx = fy + self.a / 500.0;
+        let fz = fy - self.b / 200.0;
+        Xyz {
+            x: white.x * lab_f_inv(fx),
+            y: white.y * lab_f_inv(fy),
+            z: white.z * lab_f_inv(fz),
+        }
+    }
+
+    /// D65 CIELAB back to sRGB.
+    pub fn to_srgb(self) -> Srgb {
+        self.to_xyz(D65).to_srgb()
+    }
+
+    pub fn to_lch(self) -> Lch {
+        let (c, h) = polar(self.a, self.b);
+        Lch { l: self.l, c, h }
+    }
+}
+
+impl Lch {
+    pub const fn new(l: f32, c: f32, h: f32) -> Self {
+        Self { l, c, h }
+    }
+
+    pub fn to_lab(self) -> Lab {
+        let (a, b) = cartesian(self.c, self.h);
+        Lab { l: self.l, a, b }
+    }
+}
+
+impl Oklab {
+    pub const fn new(l: f32, a: f32, b: f32) -> Self {
+        Self { l, a, b }
+    }
+
+    pub fn to_linear_rgb(self) -> LinearRgb {
+        let l = self.l + 0.396_337_78 * self.a + 0.215_803_76 * self.b;
+        let m = self.l - 0.105_561_346 * self.a - 0.063_854_17 * self.b;
+        let s = self.l - 0.089_484_18 * self.a - 1.291_485_5 * self.b;
+        let (l, m, s) = (l * l * l, m * m * m, s * s * s);
+        LinearRgb::new(
+            4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s,
+            -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s,
+            -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s,
+        )
+    }
+
+    pub fn to_srgb(self) -> Srgb {
+        self.to_linear_rgb().to_srgb()
+    }
+
+    pub fn to_oklch(self) -> Oklch {
+        let (c, h) = polar(self.a, self.b);
+        Oklch { l: self.l, c, h }
+    }
+}
+
+impl Oklch {
+    pub const fn new(l: f32, c: f32, h: f32) -> Self {
+        Self { l, c, h }
+    }
+
+    pub fn to_oklab(self) -> Oklab {
+        let (a, b) = cartesian(self.c, self.h);
+        Oklab { l: self.l, a, b }
+    }
+}
+
+impl Hsv {
+    pub fn to_srgb(self) -> Srgb {
+        let c = self.v * self.s;
+        hexcone(self.h, c, self.v - c)
+    }
+}
+
+impl Hsl {
+    pub fn to_srgb(self) -> Srgb {
+        let c = (1.0 - (2.0 * self.l - 1.0).abs()) * self.s;
+        hexcone(self.h, c, self.l - c / 2.0)
+    }
+}
+
+/// Shared HSV/HSL inverse: chroma `c` on the hue's sextant, lifted by `m`.
+fn hexcone(h: f32, c: f32, m: f32) -> Srgb {
+    let h = wrap_hue(h) / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    Srgb::new(r + m, g + m, b + m)
+}
+
+impl Cmyk {
+    pub fn to_srgb(self) -> Srgb {
+        Srgb::new(
+            (1.0 - self.c) * (1.0 - self.k),
+            (1.0 - self.m) * (1.0 - self.k),
+            (1.0 - self.y) * (1.0 - self.k),
+        )
+    }
+}
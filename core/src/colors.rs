@@ -0,0 +1,100 @@
+use core::fmt;
+
+use embedded_graphics::pixelcolor::Rgb888;
+
+use crate::color_space::Lab;
+use crate::describe::Description;
+use crate::kdtree;
+use crate::lab::DeltaE;
+use crate::palette::Palette;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NamedColor {
+    pub name: &'static str,
+    pub color: Rgb888,
+    /// Set when the color is described rather than named; shown in place
+    /// of `name`.
+    pub description: Option<Description>,
+}
+
+impl fmt::Display for NamedColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.description {
+            Some(description) => description.fmt(f),
+            None => f.write_str(self.name),
+        }
+    }
+}
+
+/// Result of matching a sample against a palette.
+#[derive(Debug, Clone, Copy)]
+pub struct Match {
+    pub name: &'static str,
+    /// Catalogue code, for palettes that have them.
+    pub code: Option<&'static str>,
+    /// The palette entry's own color, not the sample's.
+    pub color: Rgb888,
+    /// Delta E between the sample and the entry, in the formula's units.
+    pub distance: f32,
+}
+
+/// "No close match" thresholds to choose from, in CIEDE2000 units. Beyond
+/// the threshold the nearest name is more misleading than helpful.
+pub const MAX_DISTANCES: [f32; 4] = [5.0, 10.0, 15.0, 25.0];
+
+/// Index into `MAX_DISTANCES` of the default threshold.
+pub const DEFAULT_MAX_DISTANCE: usize = 2;
+
+/// The closest palette entries to a sample, nearest first.
+#[derive(Debug, Clone)]
+pub struct Matches<const N: usize> {
+    pub entries: heapless::Vec<Match, N>,
+    /// How many of `entries` were within the threshold.
+    pub close: usize,
+}
+
+impl<const N: usize> Matches<N> {
+    /// The nearest entry, or `None` if even that is beyond the threshold.
+    pub fn best(&self) -> Option<&Match> {
+        self.entries[..self.close].first()
+    }
+
+    /// Runners-up that are also within the threshold.
+    pub fn also_close(&self) -> &[Match] {
+        &self.entries[self.close.min(1)..self.close]
+    }
+}
+
+/// Find the `N` entries of `palette` perceptually nearest to a sample, using
+/// `formula` in CIELAB. Searches the palette's build-time index rather than
+/// scanning every entry. `max_distance` is in CIEDE2000 units and is scaled
+/// to `formula`'s.
+pub fn match_colors<const N: usize>(
+    palette: &Palette,
+    r: u8,
+    g: u8,
+    b: u8,
+    formula: DeltaE,
+    max_distance: f32,
+) -> Matches<N> {
+    let sample = Lab::from_srgb8(r, g, b);
+    let nearest = kdtree::nearest::<N>(palette.index, |i| palette.entries[i].lab, &sample, formula);
+
+    let mut entries = heapless::Vec::<Match, N>::new();
+    for &(i, distance) in nearest.as_slice() {
+        let c = &palette.entries[i as usize];
+        let _ = entries.push(Match {
+            name: c.name,
+            code: c.code,
+            color: c.color,
+            distance,
+        });
+    }
+
+    let max_distance = formula.threshold(max_distance);
+    let close = entries
+        .iter()
+        .take_while(|m| m.distance <= max_distance)
+        .count();
+    Matches { entries, close }
+}
@@ -0,0 +1,112 @@
+//! Text/background contrast: WCAG 2.x contrast ratio and APCA.
+//!
+//! WCAG 2.x is what accessibility requirements cite today. APCA (the
+//! candidate method for WCAG 3) models perceived lightness contrast better,
+//! notably for light text on dark backgrounds, so both are reported.
+
+use embedded_graphics::pixelcolor::Rgb888;
+use embedded_graphics::prelude::RgbColor;
+use libm::powf;
+
+use crate::color_space::decode_srgb8;
+use crate::colors::NamedColor;
+
+/// WCAG 2.x thresholds: (normal text, large text).
+const AA: (f32, f32) = (4.5, 3.0);
+const AAA: (f32, f32) = (7.0, 4.5);
+
+/// WCAG 2.x relative luminance, 0 (black) to 1 (white).
+///
+/// The spec's linearization threshold is 0.03928 rather than sRGB's
+/// 0.04045; no 8-bit value falls between the two, so the sRGB curve is used.
+pub fn relative_luminance(c: Rgb888) -> f32 {
+    0.2126 * decode_srgb8(c.r()) + 0.7152 * decode_srgb8(c.g()) + 0.0722 * decode_srgb8(c.b())
+}
+
+/// WCAG 2.x contrast ratio, 1 to 21. Symmetric in its arguments.
+pub fn contrast_ratio(a: Rgb888, b: Rgb888) -> f32 {
+    let (la, lb) = (relative_luminance(a), relative_luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+/// APCA lightness contrast (Lc, APCA-W3 0.0.98G-4g). Positive for dark text
+/// on a light background, negative for light on dark; about ±106 at most.
+pub fn apca_contrast(text: Rgb888, background: Rgb888) -> f32 {
+    const NORM_BG: f32 = 0.56;
+    const NORM_TXT: f32 = 0.57;
+    const REV_TXT: f32 = 0.62;
+    const REV_BG: f32 = 0.65;
+    const BLK_THRS: f32 = 0.022;
+    const BLK_CLMP: f32 = 1.414;
+    const SCALE: f32 = 1.14;
+    const LO_OFFSET: f32 = 0.027;
+    const LO_CLIP: f32 = 0.1;
+    const DELTA_Y_MIN: f32 = 0.0005;
+
+    // APCA's own screen luminance: a plain 2.4 gamma, with a soft clamp near black
+    let y = |c: Rgb888| {
+        let ch = |v: u8| powf(v as f32 / 255.0, 2.4);
+        let y = 0.212_672_9 * ch(c.r()) + 0.715_152_2 * ch(c.g()) + 0.072_175 * ch(c.b());
+        if y < BLK_THRS {
+            y + powf(BLK_THRS - y, BLK_CLMP)
+        } else {
+            y
+        }
+    };
+    let (y_txt, y_bg) = (y(text), y(background));
+    if (y_bg - y_txt).abs() < DELTA_Y_MIN {
+        return 0.0;
+    }
+
+    let lc = if y_bg > y_txt {
+        let sapc = (powf(y_bg, NORM_BG) - powf(y_txt, NORM_TXT)) * SCALE;
+        if sapc < LO_CLIP {
+            0.0
+        } else {
+            sapc - LO_OFFSET
+        }
+    } else {
+        let sapc = (powf(y_bg, REV_BG) - powf(y_txt, REV_TXT)) * SCALE;
+        if sapc > -LO_CLIP {
+            0.0
+        } else {
+            sapc + LO_OFFSET
+        }
+    };
+    lc * 100.0
+}
+
+/// Contrast of a foreground (text) color against a background.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contrast {
+    pub ratio: f32,
+    /// APCA Lc, signed by polarity
+    pub apca: f32,
+}
+
+impl Contrast {
+    pub fn between(foreground: &NamedColor, background: &NamedColor) -> Self {
+        Self {
+            ratio: contrast_ratio(foreground.color, background.color),
+            apca: apca_contrast(foreground.color, background.color),
+        }
+    }
+
+    /// WCAG AA for normal-size text (below 18pt, or 14pt bold).
+    pub fn aa_normal(&self) -> bool {
+        self.ratio >= AA.0
+    }
+
+    /// WCAG AA for large text (18pt and up, or 14pt bold).
+    pub fn aa_large(&self) -> bool {
+        self.ratio >= AA.1
+    }
+
+    pub fn aaa_normal(&self) -> bool {
+        self.ratio >= AAA.0
+    }
+
+    pub fn aaa_large(&self) -> bool {
+        self.ratio >= AAA.1
+    }
+}
@@ -0,0 +1,105 @@
+//! Descriptive color names built from CIELAB lightness, chroma and hue,
+//! e.g. "muted dark green".
+//!
+//! The hue term is one of Berlin and Kay's eleven basic color terms, which
+//! nearly every language with that many terms draws in the same places.
+//! Unlike a palette lookup this names every color, so it stays useful far
+//! from any palette entry.
+
+use core::fmt;
+
+use crate::color_space::Lab;
+
+/// Longest description, e.g. "grayish pale purple"; fits a 130 px line in
+/// a 6 px font.
+pub const MAX_LEN: usize = 20;
+
+/// Below this chroma a color is named black, gray or white.
+const ACHROMATIC: f32 = 8.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Description {
+    /// "grayish", "muted", "vivid", or none for ordinary chroma
+    pub chroma: Option<&'static str>,
+    /// "deep" to "pale", or none for mid lightness
+    pub lightness: Option<&'static str>,
+    /// Basic color term
+    pub hue: &'static str,
+}
+
+impl fmt::Display for Description {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for word in [self.chroma, self.lightness].into_iter().flatten() {
+            write!(f, "{} ", word)?;
+        }
+        f.write_str(self.hue)
+    }
+}
+
+pub fn describe(lab: &Lab) -> Description {
+    let lch = lab.to_lch();
+    let (l, c) = (lch.l, lch.c);
+
+    if c < ACHROMATIC {
+        return match l {
+            l if l < 15.0 => plain("black"),
+            l if l > 92.0 => plain("white"),
+            l => Description {
+                chroma: None,
+                lightness: lightness(l),
+                hue: "gray",
+            },
+        };
+    }
+
+    Description {
+        chroma: match c {
+            c if c < 20.0 => Some("grayish"),
+            c if c < 35.0 => Some("muted"),
+            c if c >= 70.0 => Some("vivid"),
+            _ => None,
+        },
+        lightness: lightness(l),
+        hue: hue_term(l, lch.h),
+    }
+}
+
+fn plain(hue: &'static str) -> Description {
+    Description {
+        chroma: None,
+        lightness: None,
+        hue,
+    }
+}
+
+fn lightness(l: f32) -> Option<&'static str> {
+    match l {
+        l if l < 20.0 => Some("deep"),
+        l if l < 38.0 => Some("dark"),
+        l if l < 62.0 => None,
+        l if l < 80.0 => Some("light"),
+        _ => Some("pale"),
+    }
+}
+
+/// Hue angle (CIELAB, degrees) to a basic term. Brown is dark orange or
+/// yellow and pink is light red or red-purple, so lightness decides those.
+/// sRGB blue sits at 306°, much further round than in HSV.
+fn hue_term(l: f32, h: f32) -> &'static str {
+    let h = if h < 0.0 { h + 360.0 } else { h };
+    if (45.0..100.0).contains(&h) && l < 50.0 {
+        return "brown";
+    }
+    if !(45.0..330.0).contains(&h) && l > 65.0 {
+        return "pink";
+    }
+    match h {
+        h if h < 45.0 => "red",
+        h if h < 85.0 => "orange",
+        h if h < 115.0 => "yellow",
+        h if h < 190.0 => "green",
+        h if h < 310.0 => "blue",
+        h if h < 345.0 => "purple",
+        _ => "red",
+    }
+}
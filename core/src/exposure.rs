@@ -0,0 +1,157 @@
+//! Automatic exposure control for the TCS34725.
+//!
+//! Watches the clear channel and walks a ladder of integration time / gain
+//! settings until the reading lands in a usable window: far enough from the
+//! ceiling that no channel clips, and high enough that `r * 255 / c` is not
+//! dominated by a handful of counts.
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::I2c;
+
+pub use crate::sensor::ExposureStatus;
+use crate::tcs34725::{asynch, Error, Gain, IntegrationTime, Rgbc, Tcs34725};
+
+/// Exposure settings ordered from least to most sensitive.
+pub const LADDER: &[(IntegrationTime, Gain)] = &[
+    (IntegrationTime::Ms2_4, Gain::X1),
+    (IntegrationTime::Ms24, Gain::X1),
+    (IntegrationTime::Ms24, Gain::X4),
+    (IntegrationTime::Ms50, Gain::X4),
+    (IntegrationTime::Ms101, Gain::X4),
+    (IntegrationTime::Ms101, Gain::X16),
+    (IntegrationTime::Ms154, Gain::X16),
+    (IntegrationTime::Ms154, Gain::X60),
+    (IntegrationTime::Ms700, Gain::X60),
+];
+
+/// Ladder position matching `tcs34725::DEFAULT_INTEGRATION_TIME` / `DEFAULT_GAIN`.
+const DEFAULT_STEP: usize = 3;
+
+/// Most settings changes tried by a single `read()`.
+const MAX_ATTEMPTS: usize = 4;
+
+/// A sample plus how trustworthy its exposure is.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Measurement {
+    pub rgbc: Rgbc,
+    pub status: ExposureStatus,
+}
+
+pub struct AutoExposure {
+    step: usize,
+}
+
+impl AutoExposure {
+    pub fn new() -> Self {
+        Self { step: DEFAULT_STEP }
+    }
+
+    /// Settings for the current ladder position.
+    pub fn settings(&self) -> (IntegrationTime, Gain) {
+        LADDER[self.step]
+    }
+
+    /// Classify `rgbc` and move one rung toward the usable window.
+    /// Returns the status and whether the settings changed.
+    pub fn update(&mut self, rgbc: &Rgbc) -> (ExposureStatus, bool) {
+        let status = ExposureStatus::classify(rgbc.c, rgbc.integration_time.max_count());
+        let next = match status {
+            ExposureStatus::Saturated if self.step > 0 => self.step - 1,
+            ExposureStatus::Underexposed if self.step + 1 < LADDER.len() => self.step + 1,
+            _ => self.step,
+        };
+        let changed = next != self.step;
+        self.step = next;
+        (status, changed)
+    }
+
+    /// Read the sensor, re-ranging until the sample is valid or the ladder runs out.
+    /// Fails with `Error::Saturated` if the clear channel clips even on the
+    /// least sensitive rung.
+    ///
+    /// The data registers keep the previous cycle's result after a settings
+    /// change, so this waits out both the old and the new integration time
+    /// before reading again.
+    pub fn read<I2C, E, D>(
+        &mut self,
+        sensor: &mut Tcs34725<I2C>,
+        delay: &mut D,
+    ) -> Result<Measurement, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        D: DelayNs,
+    {
+        self.apply(sensor, delay)?;
+
+        let mut attempts = 0;
+        loop {
+            let rgbc = sensor.read_all()?;
+            let (status, changed) = self.update(&rgbc);
+            attempts += 1;
+            if !changed || attempts >= MAX_ATTEMPTS {
+                return self.finish(Measurement { rgbc, status });
+            }
+            self.apply(sensor, delay)?;
+        }
+    }
+
+    /// Async counterpart of `read()`. The async driver starts a fresh
+    /// integration cycle for every sample, so no settling delay is needed.
+    pub async fn read_async<I2C, E, D>(
+        &mut self,
+        sensor: &mut asynch::Tcs34725<I2C>,
+        delay: &mut D,
+    ) -> Result<Measurement, Error<E>>
+    where
+        I2C: embedded_hal_async::i2c::I2c<Error = E>,
+        D: embedded_hal_async::delay::DelayNs,
+    {
+        let mut attempts = 0;
+        loop {
+            let (integration_time, gain) = self.settings();
+            if sensor.integration_time() != integration_time {
+                sensor.set_integration_time(integration_time).await?;
+            }
+            if sensor.gain() != gain {
+                sensor.set_gain(gain).await?;
+            }
+
+            let rgbc = sensor.read_all(delay).await?;
+            let (status, changed) = self.update(&rgbc);
+            attempts += 1;
+            if !changed || attempts >= MAX_ATTEMPTS {
+                return self.finish(Measurement { rgbc, status });
+            }
+        }
+    }
+
+    fn finish<E>(&self, measurement: Measurement) -> Result<Measurement, Error<E>> {
+        let rgbc = &measurement.rgbc;
+        if self.step == 0 && rgbc.c >= rgbc.integration_time.max_count() {
+            return Err(Error::Saturated);
+        }
+        Ok(measurement)
+    }
+
+    fn apply<I2C, E, D>(&self, sensor: &mut Tcs34725<I2C>, delay: &mut D) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        D: DelayNs,
+    {
+        let (integration_time, gain) = self.settings();
+        if sensor.integration_time() == integration_time && sensor.gain() == gain {
+            return Ok(());
+        }
+        let previous = sensor.integration_time();
+        sensor.set_integration_time(integration_time)?;
+        sensor.set_gain(gain)?;
+        delay.delay_us(previous.micros() + integration_time.micros());
+        Ok(())
+    }
+}
+
+impl Default for AutoExposure {
+    fn default() -> Self {
+        Self::new()
+    }
+}
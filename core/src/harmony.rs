@@ -0,0 +1,107 @@
+//! Color harmonies around a base color.
+//!
+//! Hue offsets are applied in OkLCh, where equal hue steps look like equal
+//! steps, rather than HSV, where the classic wheel bunches greens together
+//! and spreads blues apart. Rotated colors keep the base lightness; any that
+//! land outside sRGB have their chroma reduced until they fit.
+
+use crate::color_space::{Oklch, Srgb};
+
+/// Colors per scheme, including the base.
+pub const MAX_COLORS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Scheme {
+    #[default]
+    Complementary,
+    SplitComplementary,
+    Triadic,
+    Tetradic,
+    Analogous,
+    Monochromatic,
+}
+
+impl Scheme {
+    pub fn label(self) -> &'static str {
+        match self {
+            Scheme::Complementary => "Complementary",
+            Scheme::SplitComplementary => "Split compl.",
+            Scheme::Triadic => "Triadic",
+            Scheme::Tetradic => "Tetradic",
+            Scheme::Analogous => "Analogous",
+            Scheme::Monochromatic => "Monochromatic",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Scheme::Complementary => Scheme::SplitComplementary,
+            Scheme::SplitComplementary => Scheme::Triadic,
+            Scheme::Triadic => Scheme::Tetradic,
+            Scheme::Tetradic => Scheme::Analogous,
+            Scheme::Analogous => Scheme::Monochromatic,
+            Scheme::Monochromatic => Scheme::Complementary,
+        }
+    }
+
+    /// Hue offsets in degrees, base first.
+    fn hue_offsets(self) -> &'static [f32] {
+        match self {
+            Scheme::Complementary => &[0.0, 180.0],
+            Scheme::SplitComplementary => &[0.0, 150.0, 210.0],
+            Scheme::Triadic => &[0.0, 120.0, 240.0],
+            Scheme::Tetradic => &[0.0, 90.0, 180.0, 270.0],
+            Scheme::Analogous => &[0.0, -30.0, 30.0, -60.0, 60.0],
+            Scheme::Monochromatic => &[0.0],
+        }
+    }
+}
+
+/// OkLCh lightness steps for the monochromatic scheme, relative to the base.
+const MONO_STEPS: [f32; MAX_COLORS] = [0.0, -0.2, -0.1, 0.1, 0.2];
+
+/// The scheme's colors in gamma-encoded sRGB, base first.
+pub fn generate(base: Srgb, scheme: Scheme) -> heapless::Vec<Srgb, MAX_COLORS> {
+    let lch = base.to_oklab().to_oklch();
+    let mut colors = heapless::Vec::new();
+
+    if scheme == Scheme::Monochromatic {
+        for step in MONO_STEPS {
+            let l = (lch.l + step).clamp(0.05, 0.97);
+            let _ = colors.push(fit_gamut(Oklch::new(l, lch.c, lch.h)));
+        }
+        return colors;
+    }
+
+    // No need to wrap the hue; it only goes through sin/cos
+    for offset in scheme.hue_offsets() {
+        let _ = colors.push(fit_gamut(Oklch::new(lch.l, lch.c, lch.h + offset)));
+    }
+    colors
+}
+
+/// Reduce chroma until the color fits in sRGB, keeping lightness and hue.
+fn fit_gamut(lch: Oklch) -> Srgb {
+    const EPS: f32 = 1e-4;
+    let in_gamut = |c: Oklch| {
+        let rgb = c.to_oklab().to_linear_rgb();
+        [rgb.r, rgb.g, rgb.b]
+            .iter()
+            .all(|v| (-EPS..=1.0 + EPS).contains(v))
+    };
+    if in_gamut(lch) {
+        return lch.to_oklab().to_srgb();
+    }
+
+    // Bisect on chroma; 12 halvings resolve well below one 8-bit step
+    let (mut lo, mut hi) = (0.0, lch.c);
+    for _ in 0..12 {
+        let mid = (lo + hi) / 2.0;
+        if in_gamut(Oklch::new(lch.l, mid, lch.h)) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Oklch::new(lch.l, lo, lch.h).to_oklab().to_srgb()
+}
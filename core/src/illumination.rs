@@ -0,0 +1,104 @@
+//! Illumination LED control and ambient-light subtraction.
+//!
+//! Most TCS34725 breakouts (DFRobot, Adafruit) carry a white LED with an
+//! enable pin. With the LED alone the reading still includes whatever room
+//! light gets past the sample, so the same swatch reads differently by a
+//! window than under a desk lamp. In differential mode each read takes one
+//! sample with the LED on and one with it off and reports the difference,
+//! which only the LED lit.
+
+use embedded_hal::digital::OutputPin;
+
+use crate::sensor::{ColorSensor, Error, Reading};
+
+/// Wraps any `ColorSensor` with an LED on an output pin (high = on).
+pub struct Illuminated<S, LED> {
+    sensor: S,
+    led: LED,
+    differential: bool,
+}
+
+impl<S: ColorSensor, LED: OutputPin> Illuminated<S, LED> {
+    /// Starts in differential mode with the LED off.
+    pub fn new(sensor: S, mut led: LED) -> Self {
+        // RP2350 GPIO writes can't fail; other HALs' pin errors are ignored too
+        let _ = led.set_low();
+        Self {
+            sensor,
+            led,
+            differential: true,
+        }
+    }
+
+    /// Switch between LED-on-minus-LED-off reads and plain ambient reads
+    /// (LED off), e.g. for the light meter.
+    pub fn set_differential(&mut self, differential: bool) {
+        self.differential = differential;
+    }
+
+    pub fn set_led(&mut self, on: bool) {
+        let _ = if on {
+            self.led.set_high()
+        } else {
+            self.led.set_low()
+        };
+    }
+
+    async fn read_differential(&mut self) -> Result<Reading, Error<S::BusError>> {
+        self.set_led(true);
+        let lit = self.sensor.read().await;
+        // Leave the LED off between reads, even if the lit read failed
+        self.set_led(false);
+        let lit = lit?;
+        // Same integration time and gain as the lit read, so the counts
+        // subtract directly and the lit read's exposure status still holds
+        let ambient = self.sensor.read_fixed().await?;
+
+        let mut reading = Reading {
+            r: lit.r.saturating_sub(ambient.r),
+            g: lit.g.saturating_sub(ambient.g),
+            b: lit.b.saturating_sub(ambient.b),
+            c: lit.c.saturating_sub(ambient.c),
+            // Lux and CCT describe the room, not the LED
+            lux: ambient.lux,
+            cct: ambient.cct,
+            ..lit
+        };
+        reading.meta.ambient = Some(if lit.c > 0 {
+            ambient.c as f32 / lit.c as f32
+        } else {
+            1.0
+        });
+        Ok(reading)
+    }
+}
+
+impl<S: ColorSensor, LED: OutputPin> ColorSensor for Illuminated<S, LED> {
+    type BusError = S::BusError;
+
+    async fn init(&mut self) -> Result<(), Error<S::BusError>> {
+        self.set_led(false);
+        self.sensor.init().await
+    }
+
+    async fn read(&mut self) -> Result<Reading, Error<S::BusError>> {
+        if self.differential {
+            self.read_differential().await
+        } else {
+            self.sensor.read().await
+        }
+    }
+
+    async fn sleep(&mut self) -> Result<(), Error<S::BusError>> {
+        self.set_led(false);
+        self.sensor.sleep().await
+    }
+
+    async fn wake(&mut self) -> Result<(), Error<S::BusError>> {
+        self.sensor.wake().await
+    }
+
+    async fn wait_for_change(&mut self) -> Result<(), Error<S::BusError>> {
+        self.sensor.wait_for_change().await
+    }
+}
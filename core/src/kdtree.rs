@@ -0,0 +1,257 @@
+//! Nearest-neighbour search over a palette in CIELAB.
+//!
+//! `build.rs` lays each palette out as an implicit k-d tree: the node for
+//! range `lo..hi` sits at `(lo + hi) / 2`, splits on `axis`, and carries the
+//! bounding box of its whole subtree. A subtree is skipped when a lower
+//! bound on the delta E to anything in its box already exceeds the worst
+//! match kept so far, so the result is the same as a linear scan.
+//!
+//! Only uses `core`, `color_space` and `lab`, so `build.rs` can include it
+//! to build the trees.
+
+use libm::{atan2f, expf, powf, sinf, sqrtf};
+
+use crate::color_space::Lab;
+use crate::lab::DeltaE;
+
+/// One tree node. Boxes are `[l, a, b]`.
+#[derive(Debug, Clone, Copy)]
+pub struct Node {
+    /// Index into the palette's entries
+    pub entry: u16,
+    /// Split axis: 0 = L, 1 = a, 2 = b
+    pub axis: u8,
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+    /// Largest Lab chroma in the subtree, for the CIE94/CIEDE2000 weights
+    pub max_chroma: f32,
+}
+
+/// Up to `N` `(entry, distance)` pairs, nearest first. Equal distances keep
+/// the lower entry index first, like a linear scan in table order.
+#[derive(Debug, Clone, Copy)]
+pub struct Nearest<const N: usize> {
+    items: [(u16, f32); N],
+    len: usize,
+}
+
+impl<const N: usize> Nearest<N> {
+    pub fn new() -> Self {
+        Self {
+            items: [(0, 0.0); N],
+            len: 0,
+        }
+    }
+
+    pub fn as_slice(&self) -> &[(u16, f32)] {
+        &self.items[..self.len]
+    }
+
+    /// Distance a candidate has to beat, or `None` while there is room.
+    fn worst(&self) -> Option<f32> {
+        (self.len == N).then(|| self.items[N - 1].1)
+    }
+
+    pub fn push(&mut self, entry: u16, distance: f32) {
+        let before = |&(e, d): &(u16, f32)| distance < d || (distance == d && entry < e);
+        let pos = self.as_slice().iter().position(before).unwrap_or(self.len);
+        if pos >= N {
+            return;
+        }
+        let end = self.len.min(N - 1);
+        self.items.copy_within(pos..end, pos + 1);
+        self.items[pos] = (entry, distance);
+        self.len = (self.len + 1).min(N);
+    }
+}
+
+impl<const N: usize> Default for Nearest<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lay `labs` out as the implicit k-d tree `nearest` walks: each range's
+/// median (on its widest axis) sits at the range's midpoint. `order` is
+/// scratch space holding `0..labs.len()`; `nodes` is as long as `labs`.
+pub fn build(labs: &[Lab], order: &mut [u16], nodes: &mut [Node]) {
+    if order.is_empty() {
+        return;
+    }
+    let coords = |i: u16| {
+        let lab = &labs[i as usize];
+        [lab.l, lab.a, lab.b]
+    };
+
+    let mut min = [f32::INFINITY; 3];
+    let mut max = [f32::NEG_INFINITY; 3];
+    let mut max_chroma = 0.0f32;
+    for &i in order.iter() {
+        for (k, v) in coords(i).into_iter().enumerate() {
+            min[k] = min[k].min(v);
+            max[k] = max[k].max(v);
+        }
+        max_chroma = max_chroma.max(labs[i as usize].chroma());
+    }
+    let mut axis = 0;
+    for k in 1..3 {
+        if max[k] - min[k] > max[axis] - min[axis] {
+            axis = k;
+        }
+    }
+
+    // Ties go by entry index so the layout is reproducible
+    order.sort_unstable_by(|&x, &y| coords(x)[axis].total_cmp(&coords(y)[axis]).then(x.cmp(&y)));
+    let mid = order.len() / 2;
+    nodes[mid] = Node {
+        entry: order[mid],
+        axis: axis as u8,
+        min,
+        max,
+        max_chroma,
+    };
+
+    let (left, rest) = order.split_at_mut(mid);
+    let (left_nodes, rest_nodes) = nodes.split_at_mut(mid);
+    build(labs, left, left_nodes);
+    build(labs, &mut rest[1..], &mut rest_nodes[1..]);
+}
+
+/// The `N` entries nearest to `sample` under `formula`. `lab` maps an entry
+/// index to its color.
+pub fn nearest<const N: usize>(
+    nodes: &[Node],
+    lab: impl Fn(usize) -> Lab,
+    sample: &Lab,
+    formula: DeltaE,
+) -> Nearest<N> {
+    let mut found = Nearest::new();
+    if N > 0 {
+        let search = Search {
+            nodes,
+            lab: &lab,
+            sample,
+            sample_chroma: sample.chroma(),
+            rt_keep: rt_keep(sample),
+            formula,
+        };
+        search.visit(0, nodes.len(), &mut found);
+    }
+    found
+}
+
+const POW25_7: f32 = 6_103_515_625.0; // 25^7
+
+/// Lower bound on `1 - |RT| / 2` for any entry, from the sample's hue. The
+/// CIEDE2000 rotation term only matters when the mean hue is near 275°
+/// (blue), and the mean hue is within 90° of the sample's.
+fn rt_keep(sample: &Lab) -> f32 {
+    // 1 - sin(60°): RC <= 2 and the rotation angle is at most 30°
+    const WORST: f32 = 0.1339;
+    if sample.a == 0.0 && sample.b == 0.0 {
+        // Hue undefined, so the mean hue is the entry's
+        return WORST;
+    }
+
+    // a' = (1 + G) a with G in 0..=0.5, which turns the hue toward the b
+    // axis without leaving the quadrant
+    let hue = |a: f32| {
+        let h = atan2f(sample.b, a).to_degrees();
+        if h < 0.0 {
+            h + 360.0
+        } else {
+            h
+        }
+    };
+    let (h0, h1) = (hue(sample.a), hue(1.5 * sample.a));
+    let (lo, hi) = (h0.min(h1), h0.max(h1));
+    let to_blue = |h: f32| {
+        let d = (h - 275.0).abs();
+        d.min(360.0 - d)
+    };
+    let gap = if (lo..=hi).contains(&275.0) {
+        0.0
+    } else {
+        to_blue(lo).min(to_blue(hi))
+    };
+
+    let slack = (gap - 90.0).max(0.0) / 25.0;
+    let d_theta = 30.0 * expf(-slack * slack);
+    let rt = 2.0 * sinf((2.0 * d_theta).to_radians());
+    (1.0 - rt / 2.0 - 1e-4).max(WORST)
+}
+
+struct Search<'a, F> {
+    nodes: &'a [Node],
+    lab: &'a F,
+    sample: &'a Lab,
+    sample_chroma: f32,
+    /// Share of the CIEDE2000 chroma/hue term the rotation term can't cancel
+    rt_keep: f32,
+    formula: DeltaE,
+}
+
+impl<F: Fn(usize) -> Lab> Search<'_, F> {
+    fn visit<const N: usize>(&self, lo: usize, hi: usize, found: &mut Nearest<N>) {
+        if lo >= hi {
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        let node = &self.nodes[mid];
+        if let Some(worst) = found.worst() {
+            if self.lower_bound(node) > worst {
+                return;
+            }
+        }
+
+        let entry = (self.lab)(node.entry as usize);
+        found.push(node.entry, self.formula.distance(&entry, self.sample));
+
+        // Nearer half first so the far half is more likely to be pruned
+        let split = [entry.l, entry.a, entry.b][node.axis as usize];
+        let s = [self.sample.l, self.sample.a, self.sample.b][node.axis as usize];
+        if s < split {
+            self.visit(lo, mid, found);
+            self.visit(mid + 1, hi, found);
+        } else {
+            self.visit(mid + 1, hi, found);
+            self.visit(lo, mid, found);
+        }
+    }
+
+    /// Smallest delta E from the sample to any color in the node's box.
+    fn lower_bound(&self, node: &Node) -> f32 {
+        let s = [self.sample.l, self.sample.a, self.sample.b];
+        let gap = |i: usize| (node.min[i] - s[i]).max(s[i] - node.max[i]).max(0.0);
+        let (dl, da, db) = (gap(0), gap(1), gap(2));
+        let dab2 = da * da + db * db;
+
+        let bound = match self.formula {
+            DeltaE::Cie76 => sqrtf(dl * dl + dab2),
+            // dC² + dH² = da² + db², and SH <= SC, which grows with the
+            // reference (entry) chroma
+            DeltaE::Cie94 => {
+                let sc = 1.0 + 0.045 * node.max_chroma;
+                sqrtf(dl * dl + dab2 / (sc * sc))
+            }
+            // SL grows with |L̄ - 50|, SH <= SC, C̄' = C̄ (1 + G) grows with
+            // C̄, and the rotation term cancels at most `1 - rt_keep` of the
+            // chroma/hue part
+            DeltaE::Ciede2000 => {
+                let l_lo = (self.sample.l + node.min[0]) / 2.0 - 50.0;
+                let l_hi = (self.sample.l + node.max[0]) / 2.0 - 50.0;
+                let l50 = l_lo.abs().max(l_hi.abs());
+                let sl = 1.0 + 0.015 * l50 * l50 / sqrtf(20.0 + l50 * l50);
+
+                let c_bar = (self.sample_chroma + node.max_chroma) / 2.0;
+                let c_bar7 = powf(c_bar, 7.0);
+                let g = 0.5 * (1.0 - sqrtf(c_bar7 / (c_bar7 + POW25_7)));
+                let sc = 1.0 + 0.045 * c_bar * (1.0 + g);
+
+                sqrtf(dl * dl / (sl * sl) + self.rt_keep * dab2 / (sc * sc))
+            }
+        };
+        // Margin for f32 rounding in the distance itself
+        bound * 0.999
+    }
+}
@@ -0,0 +1,153 @@
+//! CIELAB color difference formulas.
+//!
+//! Euclidean distance in gamma-encoded sRGB overweights differences the eye
+//! barely sees (light greens) and underweights ones it sees clearly (near
+//! grays). Matching in CIELAB with a proper delta E picks the name a person
+//! would.
+
+use libm::{atan2f, cosf, expf, powf, sinf, sqrtf};
+
+pub use crate::color_space::Lab;
+
+/// Color difference formula.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeltaE {
+    /// Euclidean distance in Lab. Cheap, but overstates saturated differences.
+    Cie76,
+    /// CIE76 with chroma/hue weighting (graphic arts constants).
+    Cie94,
+    /// Current CIE recommendation; fixes CIE94's blues and near-neutrals.
+    #[default]
+    Ciede2000,
+}
+
+impl DeltaE {
+    pub fn label(self) -> &'static str {
+        match self {
+            DeltaE::Cie76 => "dE76",
+            DeltaE::Cie94 => "dE94",
+            DeltaE::Ciede2000 => "dE00",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            DeltaE::Cie76 => DeltaE::Cie94,
+            DeltaE::Cie94 => DeltaE::Ciede2000,
+            DeltaE::Ciede2000 => DeltaE::Cie76,
+        }
+    }
+
+    /// A CIEDE2000 threshold in this formula's units. The factors are the
+    /// median ratios between palette colors 5 to 25 dE00 apart, so they only
+    /// hold on average.
+    pub fn threshold(self, ciede2000: f32) -> f32 {
+        match self {
+            DeltaE::Cie76 => ciede2000 * 1.7,
+            DeltaE::Cie94 => ciede2000 * 1.1,
+            DeltaE::Ciede2000 => ciede2000,
+        }
+    }
+
+    /// Difference between a reference color and a sample. CIE94 is not
+    /// symmetric; `reference` supplies the chroma weighting.
+    pub fn distance(self, reference: &Lab, sample: &Lab) -> f32 {
+        match self {
+            DeltaE::Cie76 => cie76(reference, sample),
+            DeltaE::Cie94 => cie94(reference, sample),
+            DeltaE::Ciede2000 => ciede2000(reference, sample),
+        }
+    }
+}
+
+fn cie76(p: &Lab, q: &Lab) -> f32 {
+    let (dl, da, db) = (p.l - q.l, p.a - q.a, p.b - q.b);
+    sqrtf(dl * dl + da * da + db * db)
+}
+
+fn cie94(p: &Lab, q: &Lab) -> f32 {
+    const K1: f32 = 0.045;
+    const K2: f32 = 0.015;
+
+    let (c1, c2) = (p.chroma(), q.chroma());
+    let dl = p.l - q.l;
+    let dc = c1 - c2;
+    let (da, db) = (p.a - q.a, p.b - q.b);
+    // dH² = da² + db² - dC², which can dip below zero through rounding
+    let dh2 = (da * da + db * db - dc * dc).max(0.0);
+
+    let sc = 1.0 + K1 * c1;
+    let sh = 1.0 + K2 * c1;
+    sqrtf(dl * dl + (dc / sc) * (dc / sc) + dh2 / (sh * sh))
+}
+
+fn ciede2000(p: &Lab, q: &Lab) -> f32 {
+    const POW25_7: f32 = 6_103_515_625.0; // 25^7
+
+    let deg = |rad: f32| rad.to_degrees();
+    let rad = |deg: f32| deg.to_radians();
+    let hue = |b: f32, a: f32| {
+        if a == 0.0 && b == 0.0 {
+            0.0
+        } else {
+            let h = deg(atan2f(b, a));
+            if h < 0.0 {
+                h + 360.0
+            } else {
+                h
+            }
+        }
+    };
+
+    let c_bar = (p.chroma() + q.chroma()) / 2.0;
+    let c_bar7 = powf(c_bar, 7.0);
+    let g = 0.5 * (1.0 - sqrtf(c_bar7 / (c_bar7 + POW25_7)));
+
+    let a1 = (1.0 + g) * p.a;
+    let a2 = (1.0 + g) * q.a;
+    let c1 = sqrtf(a1 * a1 + p.b * p.b);
+    let c2 = sqrtf(a2 * a2 + q.b * q.b);
+    let h1 = hue(p.b, a1);
+    let h2 = hue(q.b, a2);
+
+    let dl = q.l - p.l;
+    let dc = c2 - c1;
+    let dh = if c1 * c2 == 0.0 {
+        0.0
+    } else if (h2 - h1).abs() <= 180.0 {
+        h2 - h1
+    } else if h2 - h1 > 180.0 {
+        h2 - h1 - 360.0
+    } else {
+        h2 - h1 + 360.0
+    };
+    let dh_big = 2.0 * sqrtf(c1 * c2) * sinf(rad(dh / 2.0));
+
+    let l_bar = (p.l + q.l) / 2.0;
+    let c_bar_p = (c1 + c2) / 2.0;
+    let h_bar = if c1 * c2 == 0.0 {
+        h1 + h2
+    } else if (h1 - h2).abs() <= 180.0 {
+        (h1 + h2) / 2.0
+    } else if h1 + h2 < 360.0 {
+        (h1 + h2 + 360.0) / 2.0
+    } else {
+        (h1 + h2 - 360.0) / 2.0
+    };
+
+    let t = 1.0 - 0.17 * cosf(rad(h_bar - 30.0))
+        + 0.24 * cosf(rad(2.0 * h_bar))
+        + 0.32 * cosf(rad(3.0 * h_bar + 6.0))
+        - 0.20 * cosf(rad(4.0 * h_bar - 63.0));
+    let d_theta = 30.0 * expf(-((h_bar - 275.0) / 25.0) * ((h_bar - 275.0) / 25.0));
+    let c_bar_p7 = powf(c_bar_p, 7.0);
+    let rc = 2.0 * sqrtf(c_bar_p7 / (c_bar_p7 + POW25_7));
+    let l50 = (l_bar - 50.0) * (l_bar - 50.0);
+    let sl = 1.0 + 0.015 * l50 / sqrtf(20.0 + l50);
+    let sc = 1.0 + 0.045 * c_bar_p;
+    let sh = 1.0 + 0.015 * c_bar_p * t;
+    let rt = -sinf(rad(2.0 * d_theta)) * rc;
+
+    let (l, c, h) = (dl / sl, dc / sc, dh_big / sh);
+    sqrtf((l * l + c * c + h * h + rt * c * h).max(0.0))
+}
@@ -0,0 +1,26 @@
+//! Hardware-independent parts of ColorPicky: the TCS34725 drivers and
+//! simulator, exposure and IR processing, calibration and the color math.
+//!
+//! The firmware crate adds the board, display, buttons and UI on top.
+
+#![no_std]
+
+pub mod adaptation;
+pub mod calibration;
+pub mod ccm;
+pub mod color_space;
+pub mod colors;
+pub mod contrast;
+pub mod describe;
+pub mod exposure;
+pub mod harmony;
+pub mod illumination;
+pub mod kdtree;
+pub mod lab;
+pub mod lux;
+pub mod mixing;
+pub mod palette;
+pub mod processing;
+pub mod sampling;
+pub mod sensor;
+pub mod tcs34725;
@@ -0,0 +1,58 @@
+//! Illuminance (lux) and correlated color temperature from raw RGBC counts,
+//! following the TCS3472x design note DN40.
+//!
+//! Counts are scaled by the integration time and gain recorded in the `Rgbc`,
+//! so results are comparable across auto-exposure steps.
+
+use crate::processing;
+use crate::tcs34725::Rgbc;
+
+/// DN40 coefficients for a TCS34725 behind open air (no cover glass).
+pub const R_COEF: f32 = 0.136;
+pub const G_COEF: f32 = 1.000;
+pub const B_COEF: f32 = -0.444;
+/// Glass attenuation factor; raise it if the sensor sits behind a window.
+pub const GA: f32 = 1.0;
+/// Device factor.
+pub const DF: f32 = 310.0;
+pub const CT_COEF: f32 = 3810.0;
+pub const CT_OFFSET: f32 = 1391.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Light {
+    pub lux: f32,
+    /// Correlated color temperature in Kelvin, `None` when there is no usable red signal.
+    pub cct: Option<f32>,
+}
+
+/// Counts per lux for the settings `rgbc` was taken with.
+fn counts_per_lux(rgbc: &Rgbc) -> f32 {
+    rgbc.integration_time.millis() * rgbc.gain.factor() as f32 / (GA * DF)
+}
+
+/// Channels with the IR component removed, as `(r, g, b)`.
+fn ir_free(rgbc: &Rgbc) -> (f32, f32, f32) {
+    let p = processing::process(rgbc);
+    (p.r as f32, p.g as f32, p.b as f32)
+}
+
+pub fn lux(rgbc: &Rgbc) -> f32 {
+    let (r, g, b) = ir_free(rgbc);
+    let g2 = R_COEF * r + G_COEF * g + B_COEF * b;
+    (g2 / counts_per_lux(rgbc)).max(0.0)
+}
+
+pub fn cct(rgbc: &Rgbc) -> Option<f32> {
+    let (r, _, b) = ir_free(rgbc);
+    if r <= 0.0 {
+        return None;
+    }
+    Some(CT_COEF * b / r + CT_OFFSET)
+}
+
+pub fn measure(rgbc: &Rgbc) -> Light {
+    Light {
+        lux: lux(rgbc),
+        cct: cct(rgbc),
+    }
+}
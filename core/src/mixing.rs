@@ -0,0 +1,137 @@
+//! Paint-mixing recipes from a set of measured base paints.
+//!
+//! Paint mixes subtractively: blue and yellow make green, not the gray an
+//! RGB average gives. Single-constant Kubelka-Munk models each paint by its
+//! absorption/scattering ratio K/S, which mixes linearly by proportion.
+//! The three linear RGB channels stand in for coarse spectral bands, so it
+//! works best with tile-calibrated readings (true reflectance).
+
+use embedded_graphics::pixelcolor::Rgb888;
+use embedded_graphics::prelude::RgbColor;
+use libm::sqrtf;
+
+use crate::color_space::{decode_srgb8, Lab, LinearRgb, D65};
+use crate::lab::DeltaE;
+
+/// Most paints in one recipe; more is impractical to mix by hand.
+pub const MAX_PAINTS: usize = 3;
+
+/// Most parts in one recipe, e.g. 6 + 3 + 1. Strong pigments tint at
+/// ratios well beyond this; the residual delta E shows when that happens.
+pub const MAX_PARTS: u8 = 10;
+
+/// Reflectance floor; K/S is infinite at zero.
+const MIN_REFLECTANCE: f32 = 0.001;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Part {
+    /// Index into the paints passed to `recipe`
+    pub paint: usize,
+    pub parts: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recipe {
+    pub parts: heapless::Vec<Part, MAX_PAINTS>,
+    /// Predicted color of the mix
+    pub color: Rgb888,
+    /// Delta E between the target and the predicted mix
+    pub delta_e: f32,
+}
+
+/// Kubelka-Munk K/S for a reflectance.
+fn ks(r: f32) -> f32 {
+    let r = r.clamp(MIN_REFLECTANCE, 1.0);
+    (1.0 - r) * (1.0 - r) / (2.0 * r)
+}
+
+/// Reflectance of an opaque layer with the given K/S.
+fn reflectance(ks: f32) -> f32 {
+    1.0 + ks - sqrtf(ks * ks + 2.0 * ks)
+}
+
+/// Predicted linear reflectance of mixing `parts` of each paint's K/S.
+fn mix(paints: &[[f32; 3]], parts: &[Part]) -> [f32; 3] {
+    let total: f32 = parts.iter().map(|p| p.parts as f32).sum();
+    let mut out = [0.0; 3];
+    for (ch, v) in out.iter_mut().enumerate() {
+        let ks_mix: f32 = parts
+            .iter()
+            .map(|p| p.parts as f32 / total * paints[p.paint][ch])
+            .sum();
+        *v = reflectance(ks_mix);
+    }
+    out
+}
+
+fn gcd(a: u8, b: u8) -> u8 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Best mix of up to `MAX_PAINTS` of `paints` (at most `MAX_PARTS` parts in
+/// total) for `target`, by exhaustive search. `None` without paints.
+pub fn recipe(target: Rgb888, paints: &[Rgb888], formula: DeltaE) -> Option<Recipe> {
+    let target_lab = Lab::from_srgb8(target.r(), target.g(), target.b());
+    let mut ks_paints = heapless::Vec::<[f32; 3], 16>::new();
+    for p in paints {
+        let _ = ks_paints.push([p.r(), p.g(), p.b()].map(|v| ks(decode_srgb8(v))));
+    }
+    let n = ks_paints.len();
+
+    let mut best: Option<(f32, heapless::Vec<Part, MAX_PAINTS>)> = None;
+    let mut consider = |parts: &[Part]| {
+        let [r, g, b] = mix(&ks_paints, parts);
+        let lab = LinearRgb::new(r, g, b).to_xyz().to_lab(D65);
+        let d = formula.distance(&target_lab, &lab);
+        // Strictly better only, so on a tie the recipe with fewer paints,
+        // searched first, wins
+        if best.as_ref().is_none_or(|(bd, _)| d < *bd) {
+            best = Some((d, heapless::Vec::from_slice(parts).unwrap_or_default()));
+        }
+    };
+    let part = |paint, parts| Part { paint, parts };
+
+    for i in 0..n {
+        consider(&[part(i, 1)]);
+    }
+    // Reduced ratios only: 2:2 is the same mix as 1:1
+    for i in 0..n {
+        for j in i + 1..n {
+            for pi in 1..MAX_PARTS {
+                for pj in 1..=MAX_PARTS - pi {
+                    if gcd(pi, pj) == 1 {
+                        consider(&[part(i, pi), part(j, pj)]);
+                    }
+                }
+            }
+        }
+    }
+    for i in 0..n {
+        for j in i + 1..n {
+            for k in j + 1..n {
+                for pi in 1..MAX_PARTS {
+                    for pj in 1..MAX_PARTS - pi {
+                        for pk in 1..=MAX_PARTS - pi - pj {
+                            if gcd(gcd(pi, pj), pk) == 1 {
+                                consider(&[part(i, pi), part(j, pj), part(k, pk)]);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let (delta_e, parts) = best?;
+    let [r, g, b] = mix(&ks_paints, &parts);
+    let (r8, g8, b8) = LinearRgb::new(r, g, b).to_srgb().to_rgb8();
+    Some(Recipe {
+        parts,
+        color: Rgb888::new(r8, g8, b8),
+        delta_e,
+    })
+}
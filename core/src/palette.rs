@@ -0,0 +1,52 @@
+//! Named color sets the matcher can switch between at runtime.
+//!
+//! Each pack beyond the built-in basic set sits behind a cargo feature
+//! (`palette-css`, `palette-ral`, `palette-xkcd`) so boards short on flash can
+//! leave it out.
+//!
+//! The entries are generated by `build.rs` from `palettes/<name>.csv`; edit
+//! the CSV, not the generated table.
+
+use embedded_graphics::pixelcolor::Rgb888;
+
+use crate::color_space::Lab;
+use crate::kdtree::Node;
+
+pub mod basic;
+#[cfg(feature = "palette-css")]
+pub mod css;
+#[cfg(feature = "palette-ral")]
+pub mod ral;
+#[cfg(feature = "palette-xkcd")]
+pub mod xkcd;
+
+/// One named color. `code` is the catalogue number for systems that have
+/// one (e.g. "RAL 3020"), shown ahead of the name.
+#[derive(Debug, Clone, Copy)]
+pub struct Entry {
+    pub name: &'static str,
+    pub code: Option<&'static str>,
+    pub color: Rgb888,
+    /// `color` in CIELAB, precomputed at build time
+    pub lab: Lab,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    pub name: &'static str,
+    pub entries: &'static [Entry],
+    /// Search tree over `entries`, see `kdtree`
+    pub index: &'static [Node],
+}
+
+/// Palettes compiled into this build, in switching order. The basic set is
+/// always first so index 0 is a safe default.
+pub static PALETTES: &[&Palette] = &[
+    &basic::PALETTE,
+    #[cfg(feature = "palette-css")]
+    &css::PALETTE,
+    #[cfg(feature = "palette-ral")]
+    &ral::PALETTE,
+    #[cfg(feature = "palette-xkcd")]
+    &xkcd::PALETTE,
+];
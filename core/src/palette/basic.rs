@@ -0,0 +1,13 @@
+//! The original hand-picked palette: common names a non-specialist would
+//! use, grouped by hue.
+
+use super::{Entry, Palette};
+
+pub const PALETTE: Palette = Palette {
+    name: "Basic",
+    entries: ENTRIES,
+    index: INDEX,
+};
+
+// Generated by build.rs from palettes/basic.csv
+include!(concat!(env!("OUT_DIR"), "/palette_basic.rs"));
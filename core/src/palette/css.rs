@@ -0,0 +1,14 @@
+//! The 148 CSS Color Module Level 4 named colors, in the spec's alphabetical
+//! order. Aliases for the same value (aqua/cyan, fuchsia/magenta and the
+//! gray/grey spellings) share one entry.
+
+use super::{Entry, Palette};
+
+pub const PALETTE: Palette = Palette {
+    name: "CSS",
+    entries: ENTRIES,
+    index: INDEX,
+};
+
+// Generated by build.rs from palettes/css.csv
+include!(concat!(env!("OUT_DIR"), "/palette_css.rs"));
@@ -0,0 +1,14 @@
+//! RAL Classic, as the sRGB approximations in common use. RAL publishes
+//! colors as physical reference cards, not screen values, so treat matches
+//! as "closest RAL to check against the card" rather than a spec lookup.
+
+use super::{Entry, Palette};
+
+pub const PALETTE: Palette = Palette {
+    name: "RAL Classic",
+    entries: ENTRIES,
+    index: INDEX,
+};
+
+// Generated by build.rs from palettes/ral.csv
+include!(concat!(env!("OUT_DIR"), "/palette_ral.rs"));
@@ -0,0 +1,15 @@
+//! Names from the xkcd color survey (Randall Munroe, 2010), in order of how
+//! often respondents used them. This is the most common 200 of the 949 names
+//! in the survey's rgb.txt; the long tail ("baby poop green") is left out
+//! to keep the flash footprint down.
+
+use super::{Entry, Palette};
+
+pub const PALETTE: Palette = Palette {
+    name: "xkcd",
+    entries: ENTRIES,
+    index: INDEX,
+};
+
+// Generated by build.rs from palettes/xkcd.csv
+include!(concat!(env!("OUT_DIR"), "/palette_xkcd.rs"));
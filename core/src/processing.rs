@@ -0,0 +1,72 @@
+//! Raw sample clean-up between `Tcs34725::read_all` and color matching,
+//! following the TCS3472x design note DN40.
+//!
+//! The RGB photodiodes also respond to infrared, which the clear channel
+//! sees as well. Their excess over the clear channel estimates the IR
+//! component, which is then removed from every channel. Samples at or near
+//! the sensor's ceiling are flagged, since IR removal and ratios are
+//! meaningless once a channel clips.
+
+use crate::tcs34725::Rgbc;
+
+/// Below this many integration cycles (~154 ms) the ADC output ripples, and
+/// counts above 75% of full scale may already be clipped.
+const RIPPLE_CYCLES: u16 = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Saturation {
+    #[default]
+    None,
+    /// Clear channel reached the full scale count for this ATIME.
+    Analog,
+    /// Clear channel is within the ripple band below full scale (short ATIME only).
+    Ripple,
+}
+
+impl Saturation {
+    pub fn is_saturated(self) -> bool {
+        self != Saturation::None
+    }
+}
+
+/// A sample with the IR component removed from each channel.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IrCorrected {
+    pub r: u16,
+    pub g: u16,
+    pub b: u16,
+    pub c: u16,
+    /// Estimated IR component, in the same counts as the channels.
+    pub ir: u16,
+    pub saturation: Saturation,
+}
+
+/// Saturation level of a sample, given the ATIME it was taken with.
+pub fn saturation(rgbc: &Rgbc) -> Saturation {
+    let max = rgbc.integration_time.max_count();
+    if rgbc.c >= max {
+        return Saturation::Analog;
+    }
+    if rgbc.integration_time.cycles() < RIPPLE_CYCLES && rgbc.c >= max - max / 4 {
+        return Saturation::Ripple;
+    }
+    Saturation::None
+}
+
+/// IR estimate: `(R + G + B - C) / 2`, clamped at zero.
+pub fn ir(rgbc: &Rgbc) -> u16 {
+    let sum = rgbc.r as u32 + rgbc.g as u32 + rgbc.b as u32;
+    (sum.saturating_sub(rgbc.c as u32) / 2) as u16
+}
+
+pub fn process(rgbc: &Rgbc) -> IrCorrected {
+    let ir = ir(rgbc);
+    IrCorrected {
+        r: rgbc.r.saturating_sub(ir),
+        g: rgbc.g.saturating_sub(ir),
+        b: rgbc.b.saturating_sub(ir),
+        c: rgbc.c.saturating_sub(ir),
+        ir,
+        saturation: saturation(rgbc),
+    }
+}
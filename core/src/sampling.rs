@@ -0,0 +1,163 @@
+//! Multi-sample averaging with outlier rejection.
+//!
+//! A single reading jitters enough to flip the matched name between close
+//! neighbours ("Gray" / "Slate Gray"). `sample()` takes a short burst,
+//! drops samples far from the per-channel median and averages the rest.
+//! Channels are compared in normalized units, so a burst stays consistent
+//! even if auto exposure changes settings half way through.
+
+use crate::sensor::{ColorSensor, Error, Reading};
+
+/// Largest burst `sample()` will take.
+pub const MAX_SAMPLES: usize = 16;
+
+/// Samples further than this many (scaled) median absolute deviations from
+/// the median on any channel are rejected.
+const OUTLIER_MADS: f32 = 3.0;
+
+/// Makes the MAD comparable to a standard deviation for normal noise.
+const MAD_SCALE: f32 = 1.4826;
+
+/// Floor for the MAD, relative to the median, so a burst of identical
+/// samples does not reject the first one that differs by a count.
+const MIN_RELATIVE_MAD: f32 = 0.01;
+
+/// Per-channel statistics, `[r, g, b, c]` in normalized units.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Stats {
+    pub mean: [f32; 4],
+    pub median: [f32; 4],
+    pub std_dev: [f32; 4],
+    /// Samples kept after outlier rejection.
+    pub used: usize,
+    pub rejected: usize,
+}
+
+impl Stats {
+    /// Worst relative standard deviation of r, g and b, in percent.
+    /// Lower means a more repeatable reading.
+    pub fn spread_percent(&self) -> f32 {
+        (0..3)
+            .map(|i| {
+                if self.mean[i] > 0.0 {
+                    self.std_dev[i] / self.mean[i] * 100.0
+                } else {
+                    0.0
+                }
+            })
+            .fold(0.0, f32::max)
+    }
+}
+
+/// The filtered reading of a burst, with how consistent the burst was.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Filtered {
+    /// Inlier mean, in the counts and settings of the last sample.
+    pub reading: Reading,
+    pub stats: Stats,
+}
+
+/// Take `n` readings (clamped to 1..=`MAX_SAMPLES`) and filter them.
+pub async fn sample<S: ColorSensor>(
+    sensor: &mut S,
+    n: usize,
+) -> Result<Filtered, Error<S::BusError>> {
+    let mut samples = heapless::Vec::<Reading, MAX_SAMPLES>::new();
+    for _ in 0..n.clamp(1, MAX_SAMPLES) {
+        let _ = samples.push(sensor.read().await?);
+    }
+    // At least one sample was pushed, so this can't be empty
+    Ok(filter(&samples).unwrap_or_default())
+}
+
+/// Filter a burst of readings. Returns `None` for an empty slice.
+pub fn filter(samples: &[Reading]) -> Option<Filtered> {
+    let last = samples.last()?;
+    let samples = &samples[..samples.len().min(MAX_SAMPLES)];
+
+    let mut values = [[0.0f32; 4]; MAX_SAMPLES];
+    for (v, s) in values.iter_mut().zip(samples) {
+        *v = s.normalized();
+    }
+    let values = &values[..samples.len()];
+
+    // Median and MAD per channel
+    let mut median = [0.0f32; 4];
+    let mut mad = [0.0f32; 4];
+    for ch in 0..4 {
+        median[ch] = median_of(values.iter().map(|v| v[ch]));
+        let dev = median_of(values.iter().map(|v| (v[ch] - median[ch]).abs()));
+        mad[ch] = (dev * MAD_SCALE).max(median[ch] * MIN_RELATIVE_MAD);
+    }
+
+    let is_inlier = |v: &[f32; 4]| {
+        (0..4).all(|ch| mad[ch] <= 0.0 || (v[ch] - median[ch]).abs() <= OUTLIER_MADS * mad[ch])
+    };
+
+    // Mean and sample standard deviation of the inliers
+    let mut sum = [0.0f32; 4];
+    let mut used = 0;
+    for v in values.iter().filter(|v| is_inlier(v)) {
+        for ch in 0..4 {
+            sum[ch] += v[ch];
+        }
+        used += 1;
+    }
+    // Every sample can fail on some channel (each one an outlier on a
+    // different channel), and an even burst's median is not a sample. Fall
+    // back to the median then, with the MAD as the spread.
+    let mean = match used {
+        0 => median,
+        _ => sum.map(|s| s / used as f32),
+    };
+
+    let mut var = [0.0f32; 4];
+    if used == 0 {
+        var = mad.map(|m| m * m);
+    } else if used > 1 {
+        for v in values.iter().filter(|v| is_inlier(v)) {
+            for ch in 0..4 {
+                var[ch] += (v[ch] - mean[ch]) * (v[ch] - mean[ch]);
+            }
+        }
+        var = var.map(|s| s / (used - 1) as f32);
+    }
+
+    // Back to counts at the settings of the most recent sample
+    let scale = last.meta.integration_us as f32 / 1000.0 * last.meta.gain as f32;
+    let to_counts = |v: f32| (v * scale + 0.5).clamp(0.0, u16::MAX as f32) as u16;
+    let reading = Reading {
+        r: to_counts(mean[0]),
+        g: to_counts(mean[1]),
+        b: to_counts(mean[2]),
+        c: to_counts(mean[3]),
+        ..*last
+    };
+
+    Some(Filtered {
+        reading,
+        stats: Stats {
+            mean,
+            median,
+            std_dev: var.map(libm::sqrtf),
+            used,
+            rejected: samples.len() - used,
+        },
+    })
+}
+
+fn median_of(values: impl Iterator<Item = f32>) -> f32 {
+    let mut buf = [0.0f32; MAX_SAMPLES];
+    let mut n = 0;
+    for v in values.take(MAX_SAMPLES) {
+        buf[n] = v;
+        n += 1;
+    }
+    let buf = &mut buf[..n];
+    buf.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal));
+    match n {
+        0 => 0.0,
+        _ if n % 2 == 1 => buf[n / 2],
+        _ => (buf[n / 2 - 1] + buf[n / 2]) / 2.0,
+    }
+}
@@ -0,0 +1,186 @@
+//! Sensor-independent interface to the color sensor.
+//!
+//! The app only talks to a `ColorSensor`. Each supported chip (selected by
+//! cargo feature) implements it and reports raw counts together with the
+//! settings that produced them, so the app can compare readings without
+//! knowing which breakout board is fitted.
+
+/// Clear channel above this fraction of full scale counts as saturated.
+/// Kept under the 75% DN40 ripple limit that applies to short TCS34725 ATIMEs.
+pub const HIGH_FRACTION: f32 = 0.70;
+/// Clear channel below this fraction of full scale counts as underexposed.
+pub const LOW_FRACTION: f32 = 0.05;
+
+/// Ambient light above this fraction of a lit reading's clear channel means
+/// the sensor is not sealed against the sample; see `illumination`.
+pub const MAX_AMBIENT_FRACTION: f32 = 0.25;
+
+/// Driver error shared by all sensor drivers.
+#[derive(Debug)]
+pub enum Error<E> {
+    /// I2C transfer failed.
+    Bus(E),
+    /// ID register holds something other than the expected part ID.
+    UnexpectedId(u8),
+    /// No completed integration cycle is available.
+    NotReady,
+    /// Clear channel clipped even at the least sensitive settings.
+    Saturated,
+    /// The interrupt GPIO could not be read.
+    Pin,
+}
+
+impl<E> Error<E> {
+    /// Short description for the fault screen.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Error::Bus(_) => "I2C bus error",
+            Error::UnexpectedId(_) => "Unknown sensor ID",
+            Error::NotReady => "Sensor not ready",
+            Error::Saturated => "Sensor saturated",
+            Error::Pin => "INT pin error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExposureStatus {
+    #[default]
+    Valid,
+    /// Clear channel is at or near the ceiling; ratios are unreliable.
+    Saturated,
+    /// Too few counts for a stable ratio.
+    Underexposed,
+}
+
+impl ExposureStatus {
+    /// Classify a clear channel count against the full scale it was taken with.
+    pub fn classify(c: u16, full_scale: u16) -> Self {
+        let max = full_scale as f32;
+        let c = c as f32;
+        if c >= max * HIGH_FRACTION {
+            ExposureStatus::Saturated
+        } else if c < max * LOW_FRACTION {
+            ExposureStatus::Underexposed
+        } else {
+            ExposureStatus::Valid
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExposureStatus::Valid => "OK",
+            ExposureStatus::Saturated => "Too bright",
+            ExposureStatus::Underexposed => "Too dark",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SensorKind {
+    #[default]
+    Tcs34725,
+    Veml6040,
+    Apds9960,
+}
+
+/// Settings and quality of a reading.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Metadata {
+    pub sensor: SensorKind,
+    /// Integration time in microseconds.
+    pub integration_us: u32,
+    /// Analog gain multiplier (1 for sensors without a gain stage).
+    pub gain: u8,
+    /// Highest count a channel can reach with these settings.
+    pub full_scale: u16,
+    pub status: ExposureStatus,
+    /// Share of the LED-lit clear channel that came from ambient light, for
+    /// differential (LED on minus LED off) readings.
+    pub ambient: Option<f32>,
+}
+
+/// One color reading. `c` is the unfiltered (clear / white) channel.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Reading {
+    pub r: u16,
+    pub g: u16,
+    pub b: u16,
+    pub c: u16,
+    pub meta: Metadata,
+    /// Illuminance in lux, if the sensor can estimate it.
+    pub lux: Option<f32>,
+    /// Correlated color temperature in Kelvin, if the sensor can estimate it.
+    pub cct: Option<f32>,
+}
+
+impl Reading {
+    /// Channels as `[r, g, b, c]` rescaled to 1x gain and 1 ms of integration,
+    /// so readings taken with different settings or sensors compare directly.
+    pub fn normalized(&self) -> [f32; 4] {
+        let scale = self.meta.integration_us as f32 / 1000.0 * self.meta.gain as f32;
+        [
+            self.r as f32 / scale,
+            self.g as f32 / scale,
+            self.b as f32 / scale,
+            self.c as f32 / scale,
+        ]
+    }
+
+    /// Too much room light reached the sensor during a differential reading.
+    pub fn ambient_leak(&self) -> bool {
+        self.meta.ambient.is_some_and(|a| a > MAX_AMBIENT_FRACTION)
+    }
+
+    /// Whether the reading can be trusted for matching.
+    pub fn is_valid(&self) -> bool {
+        self.meta.status == ExposureStatus::Valid && !self.ambient_leak()
+    }
+
+    /// Status line text; ambient leakage takes precedence over exposure.
+    pub fn status_label(&self) -> &'static str {
+        if self.ambient_leak() {
+            "Light leak"
+        } else {
+            self.meta.status.label()
+        }
+    }
+
+    /// Channels relative to the clear channel, scaled to 0-255.
+    pub fn rgb8(&self) -> Option<(u8, u8, u8)> {
+        if self.c == 0 {
+            return None;
+        }
+        let c = self.c as u32;
+        let scale = |v: u16| (v as u32 * 255 / c).min(255) as u8;
+        Some((scale(self.r), scale(self.g), scale(self.b)))
+    }
+}
+
+#[allow(async_fn_in_trait)]
+pub trait ColorSensor {
+    type BusError;
+
+    /// Verify the part and configure it for measuring. Safe to call again to
+    /// recover after a fault.
+    async fn init(&mut self) -> Result<(), Error<Self::BusError>>;
+
+    /// Take a fresh reading.
+    async fn read(&mut self) -> Result<Reading, Error<Self::BusError>>;
+
+    /// Take a fresh reading with the settings of the last `read()`, without
+    /// re-ranging, so the two can be subtracted count for count. Sensors
+    /// without auto exposure always read that way.
+    async fn read_fixed(&mut self) -> Result<Reading, Error<Self::BusError>> {
+        self.read().await
+    }
+
+    /// Power down between bursts.
+    async fn sleep(&mut self) -> Result<(), Error<Self::BusError>>;
+
+    async fn wake(&mut self) -> Result<(), Error<Self::BusError>>;
+
+    /// Wait until the scene may have changed since the last `read()`.
+    /// Sensors without a change interrupt return after a fixed interval.
+    async fn wait_for_change(&mut self) -> Result<(), Error<Self::BusError>>;
+}
@@ -0,0 +1,496 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::I2c;
+
+pub use crate::sensor::Error;
+
+pub mod asynch;
+pub mod color_sensor;
+pub mod sim;
+
+pub const ADDRESS: u8 = 0x29;
+
+pub const COMMAND_BIT: u8 = 0x80;
+pub const COMMAND_AUTO_INCREMENT: u8 = 0x20;
+pub const COMMAND_SPECIAL: u8 = 0x60;
+pub const SPECIAL_CLEAR_INTERRUPT: u8 = 0x06;
+
+pub const REG_ENABLE: u8 = 0x00;
+pub const REG_ATIME: u8 = 0x01;
+pub const REG_WTIME: u8 = 0x03;
+pub const REG_AILTL: u8 = 0x04;
+pub const REG_PERS: u8 = 0x0C;
+pub const REG_CONFIG: u8 = 0x0D;
+pub const REG_CONTROL: u8 = 0x0F;
+pub const REG_ID: u8 = 0x12;
+pub const REG_STATUS: u8 = 0x13;
+pub const REG_CDATAL: u8 = 0x14;
+
+pub const MASK_ENABLE_PON: u8 = 0x01; // Power ON
+pub const MASK_ENABLE_AEN: u8 = 0x02; // RGBC Enable
+pub const MASK_ENABLE_WEN: u8 = 0x08; // Wait Enable
+pub const MASK_ENABLE_AIEN: u8 = 0x10; // RGBC Interrupt Enable
+
+pub const MASK_CONFIG_WLONG: u8 = 0x02; // Wait cycles are 12x longer
+
+pub const MASK_STATUS_AVALID: u8 = 0x01; // RGBC integration cycle complete
+pub const MASK_STATUS_AINT: u8 = 0x10; // RGBC clear channel interrupt
+
+pub const ID_TCS34725: u8 = 0x44; // TCS34721 / TCS34725
+pub const ID_TCS34727: u8 = 0x4D; // TCS34723 / TCS34727
+
+/// Check an ID register value against the supported parts.
+fn check_id<E>(id: u8) -> Result<(), Error<E>> {
+    match id {
+        ID_TCS34725 | ID_TCS34727 => Ok(()),
+        other => Err(Error::UnexpectedId(other)),
+    }
+}
+
+/// Length of one RGBC integration cycle in microseconds.
+pub const CYCLE_US: u32 = 2400;
+
+/// Oscillator warm-up required after setting PON before RGBC may start.
+pub const WARMUP_US: u32 = 2400;
+
+/// RGBC integration time (ATIME register).
+///
+/// Longer integration collects more light, so counts (and the clear channel
+/// ceiling) grow with it. Each cycle is 2.4 ms and yields up to 1024 counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IntegrationTime {
+    /// 1 cycle, max count 1024. Power-on default.
+    #[default]
+    Ms2_4,
+    /// 10 cycles, max count 10240.
+    Ms24,
+    /// 21 cycles, max count 21504.
+    Ms50,
+    /// 42 cycles, max count 43008.
+    Ms101,
+    /// 64 cycles, max count 65535.
+    Ms154,
+    /// 256 cycles, max count 65535.
+    Ms700,
+}
+
+impl IntegrationTime {
+    /// Raw ATIME register value (256 - cycles).
+    pub const fn atime(self) -> u8 {
+        (256 - self.cycles()) as u8
+    }
+
+    /// Number of 2.4 ms integration cycles.
+    pub const fn cycles(self) -> u16 {
+        match self {
+            IntegrationTime::Ms2_4 => 1,
+            IntegrationTime::Ms24 => 10,
+            IntegrationTime::Ms50 => 21,
+            IntegrationTime::Ms101 => 42,
+            IntegrationTime::Ms154 => 64,
+            IntegrationTime::Ms700 => 256,
+        }
+    }
+
+    /// Integration time in microseconds.
+    pub const fn micros(self) -> u32 {
+        self.cycles() as u32 * CYCLE_US
+    }
+
+    /// Integration time in milliseconds.
+    pub fn millis(self) -> f32 {
+        self.micros() as f32 / 1000.0
+    }
+
+    /// Highest count any channel can reach at this integration time.
+    pub const fn max_count(self) -> u16 {
+        let max = self.cycles() as u32 * 1024;
+        if max > u16::MAX as u32 {
+            u16::MAX
+        } else {
+            max as u16
+        }
+    }
+}
+
+/// RGBC analog gain (CONTROL register, AGAIN bits).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Gain {
+    /// Power-on default.
+    #[default]
+    X1,
+    X4,
+    X16,
+    X60,
+}
+
+impl Gain {
+    /// Raw AGAIN field value.
+    pub const fn bits(self) -> u8 {
+        match self {
+            Gain::X1 => 0b00,
+            Gain::X4 => 0b01,
+            Gain::X16 => 0b10,
+            Gain::X60 => 0b11,
+        }
+    }
+
+    /// Gain multiplier.
+    pub const fn factor(self) -> u8 {
+        match self {
+            Gain::X1 => 1,
+            Gain::X4 => 4,
+            Gain::X16 => 16,
+            Gain::X60 => 60,
+        }
+    }
+}
+
+/// How many consecutive out-of-range clear readings it takes to assert INT
+/// (PERS register, APERS bits).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Persistence {
+    /// Every RGBC cycle asserts INT, regardless of thresholds.
+    #[default]
+    EveryCycle,
+    Cycles1,
+    Cycles2,
+    Cycles3,
+    Cycles5,
+    Cycles10,
+    Cycles15,
+    Cycles20,
+    Cycles25,
+    Cycles30,
+    Cycles35,
+    Cycles40,
+    Cycles45,
+    Cycles50,
+    Cycles55,
+    Cycles60,
+}
+
+impl Persistence {
+    /// Raw APERS field value.
+    pub const fn bits(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Pause between RGBC cycles while WEN is set (WTIME register plus
+/// CONFIG.WLONG). The sensor draws ~65 uA instead of ~235 uA while waiting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WaitTime {
+    /// 1..=256 wait cycles.
+    cycles: u16,
+    /// Each cycle is 28.8 ms instead of 2.4 ms.
+    long: bool,
+}
+
+impl WaitTime {
+    /// Longest wait: 256 long cycles, about 7.4 s.
+    pub const MAX: WaitTime = WaitTime {
+        cycles: 256,
+        long: true,
+    };
+
+    /// Closest wait time not shorter than `ms` (clamped to 2.4 ms..7.4 s).
+    pub fn from_millis(ms: u32) -> Self {
+        let us = ms.saturating_mul(1000);
+        let short = us.div_ceil(CYCLE_US);
+        if short <= 256 {
+            return WaitTime {
+                cycles: short.max(1) as u16,
+                long: false,
+            };
+        }
+        let long = us.div_ceil(CYCLE_US * 12);
+        WaitTime {
+            cycles: long.min(256) as u16,
+            long: true,
+        }
+    }
+
+    /// Raw WTIME register value (256 - cycles).
+    pub const fn wtime(self) -> u8 {
+        (256 - self.cycles) as u8
+    }
+
+    pub const fn is_long(self) -> bool {
+        self.long
+    }
+
+    /// Wait time in microseconds.
+    pub const fn micros(self) -> u32 {
+        let cycle = if self.long { CYCLE_US * 12 } else { CYCLE_US };
+        self.cycles as u32 * cycle
+    }
+}
+
+impl Default for WaitTime {
+    /// Power-on default: one 2.4 ms cycle.
+    fn default() -> Self {
+        WaitTime {
+            cycles: 1,
+            long: false,
+        }
+    }
+}
+
+/// Power state as implied by the ENABLE register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerState {
+    /// PON clear: oscillator off, ~2.5 uA. Register contents are kept.
+    Sleep,
+    /// Powered, RGBC engine stopped.
+    Idle,
+    /// Integrating continuously.
+    Active,
+    /// Integrating, with a WTIME pause between cycles.
+    ActiveWaiting,
+}
+
+impl PowerState {
+    fn from_enable(enable: u8) -> Self {
+        if enable & MASK_ENABLE_PON == 0 {
+            PowerState::Sleep
+        } else if enable & MASK_ENABLE_AEN == 0 {
+            PowerState::Idle
+        } else if enable & MASK_ENABLE_WEN == 0 {
+            PowerState::Active
+        } else {
+            PowerState::ActiveWaiting
+        }
+    }
+}
+
+/// Integration time written by `init()`. Leaves headroom on white paper at 1x-4x.
+pub const DEFAULT_INTEGRATION_TIME: IntegrationTime = IntegrationTime::Ms50;
+/// Gain written by `init()`.
+pub const DEFAULT_GAIN: Gain = Gain::X4;
+
+pub struct Tcs34725<I2C> {
+    i2c: I2C,
+    /// Shadow of the ENABLE register, so toggling one bit keeps the others.
+    enable: u8,
+    integration_time: IntegrationTime,
+    gain: Gain,
+    wait_time: WaitTime,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Rgbc {
+    pub r: u16,
+    pub g: u16,
+    pub b: u16,
+    pub c: u16,
+    /// Integration time the sample was taken with.
+    pub integration_time: IntegrationTime,
+    /// Gain the sample was taken with.
+    pub gain: Gain,
+}
+
+impl Rgbc {
+    /// Decode the 8-byte CDATAL..BDATAH burst read.
+    fn from_bytes(buf: &[u8; 8], integration_time: IntegrationTime, gain: Gain) -> Self {
+        Self {
+            c: u16::from_le_bytes([buf[0], buf[1]]),
+            r: u16::from_le_bytes([buf[2], buf[3]]),
+            g: u16::from_le_bytes([buf[4], buf[5]]),
+            b: u16::from_le_bytes([buf[6], buf[7]]),
+            integration_time,
+            gain,
+        }
+    }
+
+    /// Channels as `[r, g, b, c]` rescaled to 1x gain and 1 ms of integration,
+    /// so samples taken with different settings can be compared directly.
+    pub fn normalized(&self) -> [f32; 4] {
+        let scale = self.integration_time.millis() * self.gain.factor() as f32;
+        [
+            self.r as f32 / scale,
+            self.g as f32 / scale,
+            self.b as f32 / scale,
+            self.c as f32 / scale,
+        ]
+    }
+}
+
+impl<I2C, E> Tcs34725<I2C>
+where
+    I2C: I2c<Error = E>,
+{
+    pub fn new(i2c: I2C) -> Self {
+        Self {
+            i2c,
+            enable: 0,
+            integration_time: IntegrationTime::default(),
+            gain: Gain::default(),
+            wait_time: WaitTime::default(),
+        }
+    }
+
+    pub fn init<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        // Make sure there is a TCS3472x on the bus before configuring it
+        check_id(self.read_id()?)?;
+
+        // Power ON and Enable RGBC
+        self.enable(delay)?;
+
+        // Replace the power-on defaults (2.4 ms, 1x), which are too short to be useful
+        self.set_integration_time(DEFAULT_INTEGRATION_TIME)?;
+        self.set_gain(DEFAULT_GAIN)?;
+
+        Ok(())
+    }
+
+    pub fn read_id(&mut self) -> Result<u8, Error<E>> {
+        self.read_reg(REG_ID)
+    }
+
+    /// Power on (waiting out the oscillator warm-up if the sensor was
+    /// asleep) and start RGBC cycles.
+    pub fn enable<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        if self.enable & MASK_ENABLE_PON == 0 {
+            self.write_enable(self.enable | MASK_ENABLE_PON)?;
+            delay.delay_us(WARMUP_US);
+        }
+        self.write_enable(self.enable | MASK_ENABLE_PON | MASK_ENABLE_AEN)
+    }
+
+    pub fn power_state(&self) -> PowerState {
+        PowerState::from_enable(self.enable)
+    }
+
+    /// Power down the oscillator and ADCs. Settings survive; call `wake()` to resume.
+    pub fn sleep(&mut self) -> Result<(), Error<E>> {
+        self.write_enable(self.enable & !(MASK_ENABLE_PON | MASK_ENABLE_AEN))
+    }
+
+    pub fn wake<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        self.enable(delay)
+    }
+
+    pub fn set_wait_time(&mut self, wait_time: WaitTime) -> Result<(), Error<E>> {
+        self.write_reg(REG_WTIME, wait_time.wtime())?;
+        let config = if wait_time.is_long() {
+            MASK_CONFIG_WLONG
+        } else {
+            0
+        };
+        self.write_reg(REG_CONFIG, config)?;
+        self.wait_time = wait_time;
+        Ok(())
+    }
+
+    pub fn wait_time(&self) -> WaitTime {
+        self.wait_time
+    }
+
+    /// Insert the configured wait time between RGBC cycles.
+    pub fn set_wait_enabled(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        if enabled {
+            self.write_enable(self.enable | MASK_ENABLE_WEN)
+        } else {
+            self.write_enable(self.enable & !MASK_ENABLE_WEN)
+        }
+    }
+
+    /// Set the clear channel window. INT asserts when the clear count falls
+    /// below `low` or rises above `high` for the configured persistence.
+    pub fn set_interrupt_thresholds(&mut self, low: u16, high: u16) -> Result<(), Error<E>> {
+        let [low_l, low_h] = low.to_le_bytes();
+        let [high_l, high_h] = high.to_le_bytes();
+        let cmd = COMMAND_BIT | COMMAND_AUTO_INCREMENT | REG_AILTL;
+        self.i2c
+            .write(ADDRESS, &[cmd, low_l, low_h, high_l, high_h])
+            .map_err(Error::Bus)
+    }
+
+    pub fn set_persistence(&mut self, persistence: Persistence) -> Result<(), Error<E>> {
+        self.write_reg(REG_PERS, persistence.bits())
+    }
+
+    pub fn set_interrupt_enabled(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        if enabled {
+            self.write_enable(self.enable | MASK_ENABLE_AIEN)
+        } else {
+            self.write_enable(self.enable & !MASK_ENABLE_AIEN)
+        }
+    }
+
+    /// Whether the clear channel interrupt is pending (STATUS.AINT).
+    pub fn interrupt_pending(&mut self) -> Result<bool, Error<E>> {
+        Ok(self.read_reg(REG_STATUS)? & MASK_STATUS_AINT != 0)
+    }
+
+    /// Clear a pending interrupt and release the INT pin.
+    pub fn clear_interrupt(&mut self) -> Result<(), Error<E>> {
+        self.i2c
+            .write(
+                ADDRESS,
+                &[COMMAND_BIT | COMMAND_SPECIAL | SPECIAL_CLEAR_INTERRUPT],
+            )
+            .map_err(Error::Bus)
+    }
+
+    pub fn set_integration_time(
+        &mut self,
+        integration_time: IntegrationTime,
+    ) -> Result<(), Error<E>> {
+        self.write_reg(REG_ATIME, integration_time.atime())?;
+        self.integration_time = integration_time;
+        Ok(())
+    }
+
+    pub fn integration_time(&self) -> IntegrationTime {
+        self.integration_time
+    }
+
+    pub fn set_gain(&mut self, gain: Gain) -> Result<(), Error<E>> {
+        self.write_reg(REG_CONTROL, gain.bits())?;
+        self.gain = gain;
+        Ok(())
+    }
+
+    pub fn gain(&self) -> Gain {
+        self.gain
+    }
+
+    /// Read the data registers. Fails with `Error::NotReady` until the first
+    /// integration cycle after enabling has completed.
+    pub fn read_all(&mut self) -> Result<Rgbc, Error<E>> {
+        if self.read_reg(REG_STATUS)? & MASK_STATUS_AVALID == 0 {
+            return Err(Error::NotReady);
+        }
+
+        let mut buf = [0u8; 8];
+        // Auto-increment so the burst walks CDATAL..BDATAH
+        let cmd = COMMAND_BIT | COMMAND_AUTO_INCREMENT | REG_CDATAL;
+
+        self.i2c
+            .write_read(ADDRESS, &[cmd], &mut buf)
+            .map_err(Error::Bus)?;
+
+        Ok(Rgbc::from_bytes(&buf, self.integration_time, self.gain))
+    }
+
+    fn write_enable(&mut self, value: u8) -> Result<(), Error<E>> {
+        self.write_reg(REG_ENABLE, value)?;
+        self.enable = value;
+        Ok(())
+    }
+
+    fn write_reg(&mut self, reg: u8, value: u8) -> Result<(), Error<E>> {
+        let cmd = COMMAND_BIT | reg;
+        self.i2c.write(ADDRESS, &[cmd, value]).map_err(Error::Bus)
+    }
+
+    fn read_reg(&mut self, reg: u8) -> Result<u8, Error<E>> {
+        let cmd = COMMAND_BIT | reg;
+        let mut buf = [0u8; 1];
+        self.i2c
+            .write_read(ADDRESS, &[cmd], &mut buf)
+            .map_err(Error::Bus)?;
+        Ok(buf[0])
+    }
+}
@@ -0,0 +1,261 @@
+//! Async TCS34725 driver on `embedded-hal-async`.
+//!
+//! Unlike the blocking driver, `read_all` only returns samples from a
+//! completed integration cycle: it restarts the RGBC engine, sleeps for the
+//! configured ATIME and then polls AVALID, yielding to the executor between
+//! polls. Sleeps go through the `DelayNs` passed in (e.g. `embassy_time::Delay`).
+//!
+//! It can also sleep until the clear channel leaves a threshold window, by
+//! awaiting the sensor's INT line (`wait_for_interrupt`).
+
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_async::digital::Wait;
+use embedded_hal_async::i2c::I2c;
+
+use super::{
+    check_id, Error, Gain, IntegrationTime, Persistence, PowerState, Rgbc, WaitTime, ADDRESS,
+    COMMAND_AUTO_INCREMENT, COMMAND_BIT, COMMAND_SPECIAL, DEFAULT_GAIN, DEFAULT_INTEGRATION_TIME,
+    MASK_CONFIG_WLONG, MASK_ENABLE_AEN, MASK_ENABLE_AIEN, MASK_ENABLE_PON, MASK_ENABLE_WEN,
+    MASK_STATUS_AINT, MASK_STATUS_AVALID, REG_AILTL, REG_ATIME, REG_CDATAL, REG_CONFIG,
+    REG_CONTROL, REG_ENABLE, REG_ID, REG_PERS, REG_STATUS, REG_WTIME, SPECIAL_CLEAR_INTERRUPT,
+    WARMUP_US,
+};
+
+/// Interval between AVALID polls once the integration time has elapsed.
+const POLL_INTERVAL_US: u32 = super::CYCLE_US;
+
+/// Slack on top of the expected cycle length before giving up on AVALID.
+const READY_MARGIN_US: u32 = 20_000;
+
+pub struct Tcs34725<I2C> {
+    i2c: I2C,
+    /// Shadow of the ENABLE register, so toggling one bit keeps the others.
+    enable: u8,
+    integration_time: IntegrationTime,
+    gain: Gain,
+    wait_time: WaitTime,
+}
+
+impl<I2C, E> Tcs34725<I2C>
+where
+    I2C: I2c<Error = E>,
+{
+    pub fn new(i2c: I2C) -> Self {
+        Self {
+            i2c,
+            enable: 0,
+            integration_time: IntegrationTime::default(),
+            gain: Gain::default(),
+            wait_time: WaitTime::default(),
+        }
+    }
+
+    pub async fn init<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        check_id(self.read_id().await?)?;
+        self.enable(delay).await?;
+        self.set_integration_time(DEFAULT_INTEGRATION_TIME).await?;
+        self.set_gain(DEFAULT_GAIN).await?;
+        Ok(())
+    }
+
+    pub async fn read_id(&mut self) -> Result<u8, Error<E>> {
+        self.read_reg(REG_ID).await
+    }
+
+    /// Power on (waiting out the oscillator warm-up if the sensor was
+    /// asleep) and start RGBC cycles.
+    pub async fn enable<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        if self.enable & MASK_ENABLE_PON == 0 {
+            self.write_enable(self.enable | MASK_ENABLE_PON).await?;
+            delay.delay_us(WARMUP_US).await;
+        }
+        self.write_enable(self.enable | MASK_ENABLE_PON | MASK_ENABLE_AEN)
+            .await
+    }
+
+    pub fn power_state(&self) -> PowerState {
+        PowerState::from_enable(self.enable)
+    }
+
+    /// Power down the oscillator and ADCs. Settings survive; call `wake()` to resume.
+    pub async fn sleep(&mut self) -> Result<(), Error<E>> {
+        self.write_enable(self.enable & !(MASK_ENABLE_PON | MASK_ENABLE_AEN))
+            .await
+    }
+
+    pub async fn wake<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        self.enable(delay).await
+    }
+
+    pub async fn set_wait_time(&mut self, wait_time: WaitTime) -> Result<(), Error<E>> {
+        self.write_reg(REG_WTIME, wait_time.wtime()).await?;
+        let config = if wait_time.is_long() {
+            MASK_CONFIG_WLONG
+        } else {
+            0
+        };
+        self.write_reg(REG_CONFIG, config).await?;
+        self.wait_time = wait_time;
+        Ok(())
+    }
+
+    pub fn wait_time(&self) -> WaitTime {
+        self.wait_time
+    }
+
+    /// Insert the configured wait time between RGBC cycles.
+    pub async fn set_wait_enabled(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        if enabled {
+            self.write_enable(self.enable | MASK_ENABLE_WEN).await
+        } else {
+            self.write_enable(self.enable & !MASK_ENABLE_WEN).await
+        }
+    }
+
+    /// Set the clear channel window. INT asserts when the clear count falls
+    /// below `low` or rises above `high` for the configured persistence.
+    pub async fn set_interrupt_thresholds(&mut self, low: u16, high: u16) -> Result<(), Error<E>> {
+        let [low_l, low_h] = low.to_le_bytes();
+        let [high_l, high_h] = high.to_le_bytes();
+        let cmd = COMMAND_BIT | COMMAND_AUTO_INCREMENT | REG_AILTL;
+        self.i2c
+            .write(ADDRESS, &[cmd, low_l, low_h, high_l, high_h])
+            .await
+            .map_err(Error::Bus)
+    }
+
+    pub async fn set_persistence(&mut self, persistence: Persistence) -> Result<(), Error<E>> {
+        self.write_reg(REG_PERS, persistence.bits()).await
+    }
+
+    pub async fn set_interrupt_enabled(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        if enabled {
+            self.write_enable(self.enable | MASK_ENABLE_AIEN).await
+        } else {
+            self.write_enable(self.enable & !MASK_ENABLE_AIEN).await
+        }
+    }
+
+    /// Whether the clear channel interrupt is pending (STATUS.AINT).
+    pub async fn interrupt_pending(&mut self) -> Result<bool, Error<E>> {
+        Ok(self.read_reg(REG_STATUS).await? & MASK_STATUS_AINT != 0)
+    }
+
+    /// Clear a pending interrupt and release the INT pin.
+    pub async fn clear_interrupt(&mut self) -> Result<(), Error<E>> {
+        self.i2c
+            .write(
+                ADDRESS,
+                &[COMMAND_BIT | COMMAND_SPECIAL | SPECIAL_CLEAR_INTERRUPT],
+            )
+            .await
+            .map_err(Error::Bus)
+    }
+
+    /// Sleep until the sensor pulls INT low, then clear the interrupt.
+    ///
+    /// `int` is the GPIO wired to the open-drain INT output, e.g. an
+    /// `embassy_rp::gpio::Input` with a pull-up. Interrupts must be enabled
+    /// with `set_interrupt_enabled(true)`.
+    pub async fn wait_for_interrupt<P: Wait>(&mut self, int: &mut P) -> Result<(), Error<E>> {
+        int.wait_for_low().await.map_err(|_| Error::Pin)?;
+        self.clear_interrupt().await
+    }
+
+    pub async fn set_integration_time(
+        &mut self,
+        integration_time: IntegrationTime,
+    ) -> Result<(), Error<E>> {
+        self.write_reg(REG_ATIME, integration_time.atime()).await?;
+        self.integration_time = integration_time;
+        Ok(())
+    }
+
+    pub fn integration_time(&self) -> IntegrationTime {
+        self.integration_time
+    }
+
+    pub async fn set_gain(&mut self, gain: Gain) -> Result<(), Error<E>> {
+        self.write_reg(REG_CONTROL, gain.bits()).await?;
+        self.gain = gain;
+        Ok(())
+    }
+
+    pub fn gain(&self) -> Gain {
+        self.gain
+    }
+
+    /// Whether the last integration cycle has completed (STATUS.AVALID).
+    pub async fn data_ready(&mut self) -> Result<bool, Error<E>> {
+        Ok(self.read_reg(REG_STATUS).await? & MASK_STATUS_AVALID != 0)
+    }
+
+    /// Start a new integration cycle and return its result once AVALID is set,
+    /// or `Error::NotReady` if it never is.
+    ///
+    /// Toggling AEN discards the cycle in progress and clears AVALID, so the
+    /// sample always reflects the current settings and what is in front of the
+    /// sensor now.
+    pub async fn read_all<D: DelayNs>(&mut self, delay: &mut D) -> Result<Rgbc, Error<E>> {
+        self.write_enable(self.enable & !MASK_ENABLE_AEN).await?;
+        self.enable(delay).await?;
+
+        // A cycle is the integration time, plus the wait time if WEN is set
+        let wait = if self.enable & MASK_ENABLE_WEN != 0 {
+            self.wait_time.micros()
+        } else {
+            0
+        };
+        let budget = wait + READY_MARGIN_US;
+
+        delay.delay_us(self.integration_time.micros()).await;
+        let mut waited = 0;
+        while !self.data_ready().await? {
+            if waited > budget {
+                return Err(Error::NotReady);
+            }
+            delay.delay_us(POLL_INTERVAL_US).await;
+            waited += POLL_INTERVAL_US;
+        }
+
+        self.read_data().await
+    }
+
+    /// Read the data registers as they are, without waiting for a new cycle.
+    pub async fn read_data(&mut self) -> Result<Rgbc, Error<E>> {
+        let mut buf = [0u8; 8];
+        // Auto-increment so the burst walks CDATAL..BDATAH
+        let cmd = COMMAND_BIT | COMMAND_AUTO_INCREMENT | REG_CDATAL;
+
+        self.i2c
+            .write_read(ADDRESS, &[cmd], &mut buf)
+            .await
+            .map_err(Error::Bus)?;
+
+        Ok(Rgbc::from_bytes(&buf, self.integration_time, self.gain))
+    }
+
+    async fn write_enable(&mut self, value: u8) -> Result<(), Error<E>> {
+        self.write_reg(REG_ENABLE, value).await?;
+        self.enable = value;
+        Ok(())
+    }
+
+    async fn write_reg(&mut self, reg: u8, value: u8) -> Result<(), Error<E>> {
+        let cmd = COMMAND_BIT | reg;
+        self.i2c
+            .write(ADDRESS, &[cmd, value])
+            .await
+            .map_err(Error::Bus)
+    }
+
+    async fn read_reg(&mut self, reg: u8) -> Result<u8, Error<E>> {
+        let cmd = COMMAND_BIT | reg;
+        let mut buf = [0u8; 1];
+        self.i2c
+            .write_read(ADDRESS, &[cmd], &mut buf)
+            .await
+            .map_err(Error::Bus)?;
+        Ok(buf[0])
+    }
+}
@@ -0,0 +1,129 @@
+//! `ColorSensor` implementation for the TCS34725.
+//!
+//! Wraps the async driver with auto exposure, DN40 IR removal and lux/CCT,
+//! and sleeps on the INT line until the clear channel leaves the window
+//! armed around the last reading.
+
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_async::digital::Wait;
+use embedded_hal_async::i2c::I2c;
+
+use super::asynch::Tcs34725;
+use super::{Error, Persistence, WaitTime};
+use crate::exposure::{AutoExposure, Measurement};
+use crate::sensor::{ColorSensor, ExposureStatus, Metadata, Reading, SensorKind};
+use crate::{lux, processing};
+
+/// Wait between RGBC cycles while idle; INT keeps watching meanwhile.
+const IDLE_WAIT_MS: u32 = 100;
+
+pub struct AutoTcs34725<I2C, INT, D> {
+    sensor: Tcs34725<I2C>,
+    exposure: AutoExposure,
+    /// Open-drain INT output (active low, needs a pull-up).
+    int: INT,
+    delay: D,
+}
+
+impl<I2C: I2c, INT, D> AutoTcs34725<I2C, INT, D> {
+    pub fn new(i2c: I2C, int: INT, delay: D) -> Self {
+        Self {
+            sensor: Tcs34725::new(i2c),
+            exposure: AutoExposure::new(),
+            int,
+            delay,
+        }
+    }
+
+    /// Re-arm INT around a sample and turn it into a `Reading`.
+    async fn report(&mut self, measurement: Measurement) -> Result<Reading, Error<I2C::Error>> {
+        let rgbc = measurement.rgbc;
+
+        // Re-arm INT for a +-10% change in brightness
+        let margin = rgbc.c / 10;
+        self.sensor
+            .set_interrupt_thresholds(rgbc.c.saturating_sub(margin), rgbc.c.saturating_add(margin))
+            .await?;
+        self.sensor.clear_interrupt().await?;
+
+        // Report IR-free channels; IR otherwise tints everything warm
+        let corrected = processing::process(&rgbc);
+        let light = lux::measure(&rgbc);
+        // Clipped or rippling counts void the IR estimate whatever the
+        // exposure ladder made of them
+        let status = if corrected.saturation.is_saturated() {
+            ExposureStatus::Saturated
+        } else {
+            measurement.status
+        };
+        Ok(Reading {
+            r: corrected.r,
+            g: corrected.g,
+            b: corrected.b,
+            c: corrected.c,
+            meta: Metadata {
+                sensor: SensorKind::Tcs34725,
+                integration_us: rgbc.integration_time.micros(),
+                gain: rgbc.gain.factor(),
+                full_scale: rgbc.integration_time.max_count(),
+                status,
+                ambient: None,
+            },
+            lux: Some(light.lux),
+            cct: light.cct,
+        })
+    }
+}
+
+impl<I2C, INT, D> ColorSensor for AutoTcs34725<I2C, INT, D>
+where
+    I2C: I2c,
+    INT: Wait,
+    D: DelayNs,
+{
+    type BusError = I2C::Error;
+
+    async fn init(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.sensor.init(&mut self.delay).await?;
+
+        // Require two cycles outside the window before asserting INT
+        self.sensor.set_persistence(Persistence::Cycles2).await?;
+        self.sensor.set_interrupt_enabled(true).await?;
+
+        // Idle between cycles to cut sensor current
+        self.sensor
+            .set_wait_time(WaitTime::from_millis(IDLE_WAIT_MS))
+            .await?;
+        self.sensor.set_wait_enabled(true).await?;
+
+        Ok(())
+    }
+
+    async fn read(&mut self) -> Result<Reading, Error<I2C::Error>> {
+        let measurement = self
+            .exposure
+            .read_async(&mut self.sensor, &mut self.delay)
+            .await?;
+        self.report(measurement).await
+    }
+
+    async fn read_fixed(&mut self) -> Result<Reading, Error<I2C::Error>> {
+        let rgbc = self.sensor.read_all(&mut self.delay).await?;
+        let status = ExposureStatus::classify(rgbc.c, rgbc.integration_time.max_count());
+        self.report(Measurement { rgbc, status }).await
+    }
+
+    async fn sleep(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.sensor.sleep().await
+    }
+
+    async fn wake(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.sensor.wake(&mut self.delay).await
+    }
+
+    /// Waits on the pin only, so it can be dropped mid-wait (e.g. by losing
+    /// a `select`) without cutting off an I2C transfer; `read()` clears INT.
+    async fn wait_for_change(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.int.wait_for_low().await.map_err(|_| Error::Pin)
+    }
+}
@@ -0,0 +1,410 @@
+//! Register-level TCS34725 simulator.
+//!
+//! Implements the blocking and async `I2c` traits at address 0x29 and
+//! models what the drivers rely on: the command byte (repeated byte,
+//! auto-increment and the clear-interrupt special function), ENABLE, ATIME,
+//! WTIME/WLONG, CONTROL, the interrupt window and persistence, the ID
+//! register, and RGBC data that only becomes valid once an integration cycle
+//! has actually elapsed. Light levels come from a script of timed steps.
+//!
+//! Time comes from a clock function (e.g. `embassy_time::Instant`) or, when
+//! none is given, from `advance()` calls, so tests can step it by hand.
+
+use embedded_hal::i2c::{ErrorKind, ErrorType, NoAcknowledgeSource, Operation};
+
+use super::{
+    ADDRESS, COMMAND_AUTO_INCREMENT, COMMAND_BIT, COMMAND_SPECIAL, CYCLE_US, ID_TCS34725,
+    MASK_CONFIG_WLONG, MASK_ENABLE_AEN, MASK_ENABLE_AIEN, MASK_ENABLE_PON, MASK_ENABLE_WEN,
+    MASK_STATUS_AINT, MASK_STATUS_AVALID, REG_AILTL, REG_ATIME, REG_CDATAL, REG_CONFIG,
+    REG_CONTROL, REG_ENABLE, REG_ID, REG_PERS, REG_STATUS, REG_WTIME, SPECIAL_CLEAR_INTERRUPT,
+    WARMUP_US,
+};
+
+/// Most light steps a script can hold.
+pub const MAX_STEPS: usize = 16;
+
+const COMMAND_TYPE_MASK: u8 = 0x60;
+const COMMAND_ADDR_MASK: u8 = 0x1F;
+
+/// Scene brightness per channel, in counts per millisecond of integration
+/// at 1x gain (the units of `Rgbc::normalized()`).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Light {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub c: f32,
+}
+
+impl Light {
+    pub const DARK: Light = Light::new(0.0, 0.0, 0.0, 0.0);
+
+    pub const fn new(r: f32, g: f32, b: f32, c: f32) -> Self {
+        Self { r, g, b, c }
+    }
+}
+
+pub struct Tcs34725Sim {
+    regs: [u8; 0x20],
+    /// Register pointer set by the last command byte.
+    pointer: u8,
+    auto_increment: bool,
+    /// When the current run of RGBC cycles started (after warm-up).
+    cycle_start: Option<u64>,
+    /// Cycles already latched since `cycle_start`.
+    cycles_done: u64,
+    /// Consecutive cycles outside the interrupt window.
+    out_of_window: u8,
+    /// `(start_us, light)` steps, sorted by start time.
+    script: heapless::Vec<(u64, Light), MAX_STEPS>,
+    /// Replay the script with this period, if set.
+    repeat_us: Option<u64>,
+    clock: Option<fn() -> u64>,
+    manual_us: u64,
+}
+
+impl Tcs34725Sim {
+    /// A powered-down TCS34725 in the dark, using a manual clock.
+    pub fn new() -> Self {
+        let mut regs = [0u8; 0x20];
+        regs[REG_ATIME as usize] = 0xFF;
+        regs[REG_WTIME as usize] = 0xFF;
+        regs[REG_ID as usize] = ID_TCS34725;
+        Self {
+            regs,
+            pointer: 0,
+            auto_increment: false,
+            cycle_start: None,
+            cycles_done: 0,
+            out_of_window: 0,
+            script: heapless::Vec::new(),
+            repeat_us: None,
+            clock: None,
+            manual_us: 0,
+        }
+    }
+
+    /// Take time from `clock` (microseconds) instead of `advance()`.
+    pub fn with_clock(mut self, clock: fn() -> u64) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Report a different part ID, e.g. to exercise ID checks.
+    pub fn with_id(mut self, id: u8) -> Self {
+        self.regs[REG_ID as usize] = id;
+        self
+    }
+
+    /// Show `light` from `at_us` on. Steps must be added in time order;
+    /// extras beyond `MAX_STEPS` are dropped.
+    pub fn step(mut self, at_us: u64, light: Light) -> Self {
+        let _ = self.script.push((at_us, light));
+        self
+    }
+
+    /// Loop the script every `period_us`.
+    pub fn repeat(mut self, period_us: u64) -> Self {
+        self.repeat_us = Some(period_us);
+        self
+    }
+
+    /// Move the manual clock forward.
+    pub fn advance(&mut self, us: u64) {
+        self.manual_us += us;
+    }
+
+    pub fn now(&self) -> u64 {
+        self.clock.map_or(self.manual_us, |clock| clock())
+    }
+
+    /// Whether the INT line would be asserted (low).
+    pub fn int_asserted(&mut self) -> bool {
+        self.update();
+        self.regs[REG_ENABLE as usize] & MASK_ENABLE_AIEN != 0
+            && self.regs[REG_STATUS as usize] & MASK_STATUS_AINT != 0
+    }
+
+    /// Register value as the driver would read it.
+    pub fn register(&mut self, reg: u8) -> u8 {
+        self.update();
+        self.regs[(reg & COMMAND_ADDR_MASK) as usize]
+    }
+
+    fn light_at(&self, t: u64) -> Light {
+        let t = match self.repeat_us {
+            Some(period) if period > 0 => t % period,
+            _ => t,
+        };
+        self.script
+            .iter()
+            .take_while(|(start, _)| *start <= t)
+            .last()
+            .map_or(Light::DARK, |(_, light)| *light)
+    }
+
+    fn integration_cycles(&self) -> u64 {
+        256 - self.regs[REG_ATIME as usize] as u64
+    }
+
+    /// Integration plus (if enabled) wait time of one RGBC cycle.
+    fn period_us(&self) -> u64 {
+        let mut period = self.integration_cycles() * CYCLE_US as u64;
+        if self.regs[REG_ENABLE as usize] & MASK_ENABLE_WEN != 0 {
+            let mut wait = (256 - self.regs[REG_WTIME as usize] as u64) * CYCLE_US as u64;
+            if self.regs[REG_CONFIG as usize] & MASK_CONFIG_WLONG != 0 {
+                wait *= 12;
+            }
+            period += wait;
+        }
+        period
+    }
+
+    fn gain(&self) -> f32 {
+        match self.regs[REG_CONTROL as usize] & 0x03 {
+            0 => 1.0,
+            1 => 4.0,
+            2 => 16.0,
+            _ => 60.0,
+        }
+    }
+
+    /// Cycles outside the window needed before AINT, per the PERS register.
+    /// `None` for APERS = 0, which asserts AINT on every cycle.
+    fn persistence(&self) -> Option<u8> {
+        Some(match self.regs[REG_PERS as usize] & 0x0F {
+            0 => return None,
+            n @ 1..=3 => n,
+            n => (n - 3) * 5,
+        })
+    }
+
+    /// Latch any integration cycles that completed since the last access.
+    fn update(&mut self) {
+        let Some(start) = self.cycle_start else {
+            return;
+        };
+        let now = self.now();
+        let integration = self.integration_cycles() * CYCLE_US as u64;
+        if now < start + integration {
+            return;
+        }
+        let period = self.period_us();
+        let done = (now - start - integration) / period + 1;
+        if done > self.cycles_done {
+            // Only the latest cycle's data stays visible, and persistence
+            // never needs more than 60, so skip replaying long idle stretches
+            let first = done.saturating_sub(64).max(self.cycles_done);
+            for cycle in first..done {
+                self.latch(start + cycle * period + integration);
+            }
+            self.cycles_done = done;
+        }
+    }
+
+    fn latch(&mut self, end: u64) {
+        let light = self.light_at(end);
+        let cycles = self.integration_cycles();
+        let full_scale = (cycles * 1024).min(u16::MAX as u64) as f32;
+        let scale = cycles as f32 * CYCLE_US as f32 / 1000.0 * self.gain();
+        let count = |v: f32| (v * scale).clamp(0.0, full_scale) as u16;
+
+        let values = [
+            count(light.c),
+            count(light.r),
+            count(light.g),
+            count(light.b),
+        ];
+        for (i, v) in values.iter().enumerate() {
+            let [lo, hi] = v.to_le_bytes();
+            self.regs[REG_CDATAL as usize + i * 2] = lo;
+            self.regs[REG_CDATAL as usize + i * 2 + 1] = hi;
+        }
+        self.regs[REG_STATUS as usize] |= MASK_STATUS_AVALID;
+
+        // Clear channel against the AILT/AIHT window
+        let t = REG_AILTL as usize;
+        let low = u16::from_le_bytes([self.regs[t], self.regs[t + 1]]);
+        let high = u16::from_le_bytes([self.regs[t + 2], self.regs[t + 3]]);
+        let outside = values[0] < low || values[0] > high;
+        self.out_of_window = match outside {
+            true => self.out_of_window.saturating_add(1),
+            false => 0,
+        };
+        let assert = match self.persistence() {
+            None => true,
+            Some(cycles) => self.out_of_window >= cycles,
+        };
+        if assert {
+            self.regs[REG_STATUS as usize] |= MASK_STATUS_AINT;
+        }
+    }
+
+    fn write_enable(&mut self, value: u8) {
+        self.update();
+        let old = self.regs[REG_ENABLE as usize];
+        self.regs[REG_ENABLE as usize] = value;
+
+        let running =
+            value & (MASK_ENABLE_PON | MASK_ENABLE_AEN) == MASK_ENABLE_PON | MASK_ENABLE_AEN;
+        let was_running =
+            old & (MASK_ENABLE_PON | MASK_ENABLE_AEN) == MASK_ENABLE_PON | MASK_ENABLE_AEN;
+        if running && !was_running {
+            // A fresh power-up also needs the oscillator warm-up
+            let warmup = if old & MASK_ENABLE_PON == 0 {
+                WARMUP_US as u64
+            } else {
+                0
+            };
+            self.cycle_start = Some(self.now() + warmup);
+            self.cycles_done = 0;
+        } else if !running {
+            self.cycle_start = None;
+            self.regs[REG_STATUS as usize] &= !MASK_STATUS_AVALID;
+        }
+    }
+
+    fn write_byte(&mut self, value: u8) {
+        let reg = self.pointer & COMMAND_ADDR_MASK;
+        let period = self.period_us();
+        match reg {
+            REG_ENABLE => self.write_enable(value),
+            // Read-only
+            REG_ID | REG_STATUS | REG_CDATAL..=0x1B => {}
+            _ => self.regs[reg as usize] = value,
+        }
+        // New timing applies from the cycle in progress; count cycles from
+        // its start so the longer or shorter period lines up
+        if self.period_us() != period {
+            if let Some(start) = self.cycle_start {
+                self.cycle_start = Some(start + self.cycles_done * period);
+                self.cycles_done = 0;
+            }
+        }
+        if self.auto_increment {
+            self.pointer = self.pointer.wrapping_add(1);
+        }
+    }
+
+    fn read_byte(&mut self) -> u8 {
+        let value = self.regs[(self.pointer & COMMAND_ADDR_MASK) as usize];
+        if self.auto_increment {
+            self.pointer = self.pointer.wrapping_add(1);
+        }
+        value
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), ErrorKind> {
+        let Some((&command, data)) = bytes.split_first() else {
+            return Ok(());
+        };
+        if command & COMMAND_BIT == 0 {
+            // The part ignores bytes that aren't command bytes; treat it as a driver bug
+            return Err(ErrorKind::Other);
+        }
+        match command & COMMAND_TYPE_MASK {
+            COMMAND_SPECIAL => {
+                if command & COMMAND_ADDR_MASK == SPECIAL_CLEAR_INTERRUPT {
+                    self.regs[REG_STATUS as usize] &= !MASK_STATUS_AINT;
+                    self.out_of_window = 0;
+                }
+            }
+            kind => {
+                self.pointer = command & COMMAND_ADDR_MASK;
+                self.auto_increment = kind == COMMAND_AUTO_INCREMENT;
+                for &b in data {
+                    self.write_byte(b);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn run(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), ErrorKind> {
+        if address != ADDRESS {
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        }
+        self.update();
+        for op in operations {
+            match op {
+                Operation::Write(bytes) => self.write(bytes)?,
+                Operation::Read(buf) => {
+                    for b in buf.iter_mut() {
+                        *b = self.read_byte();
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for Tcs34725Sim {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ErrorType for Tcs34725Sim {
+    type Error = ErrorKind;
+}
+
+impl embedded_hal::i2c::I2c for Tcs34725Sim {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.run(address, operations)
+    }
+}
+
+impl embedded_hal_async::i2c::I2c for Tcs34725Sim {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.run(address, operations)
+    }
+}
+
+/// Stand-in for the INT pin: always asserted, so the app reads every loop
+/// (each read still takes a full simulated integration cycle).
+pub struct NoInt;
+
+impl embedded_hal::digital::ErrorType for NoInt {
+    type Error = core::convert::Infallible;
+}
+
+impl embedded_hal::digital::InputPin for NoInt {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(true)
+    }
+}
+
+impl embedded_hal_async::digital::Wait for NoInt {
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        core::future::pending().await
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        core::future::pending().await
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        core::future::pending().await
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        core::future::pending().await
+    }
+}
@@ -0,0 +1,46 @@
+//! Chromatic adaptation.
+
+use colorpicky_core::adaptation::{Cat, Illuminant};
+use colorpicky_core::color_space::{LinearRgb, Xyz, D65};
+
+fn assert_close(a: Xyz, b: Xyz) {
+    let error = (a.x - b.x)
+        .abs()
+        .max((a.y - b.y).abs())
+        .max((a.z - b.z).abs());
+    assert!(error < 1e-4, "{:?} vs {:?}", a, b);
+}
+
+#[test]
+fn white_maps_to_white() {
+    let illuminants = [
+        Illuminant::A,
+        Illuminant::D50,
+        Illuminant::F2,
+        Illuminant::F7,
+        Illuminant::F11,
+    ];
+    for cat in [Cat::Bradford, Cat::Cat16] {
+        for illuminant in illuminants {
+            let white = illuminant.white();
+            assert_close(cat.adapt(white, white, D65), D65);
+        }
+    }
+}
+
+#[test]
+fn a_measured_white_reads_neutral() {
+    // A white tile under the LED, as linear RGB
+    let tile = LinearRgb::new(0.9, 0.7, 0.45);
+    let led = Illuminant::measured(tile).unwrap();
+    for cat in [Cat::Bradford, Cat::Cat16] {
+        let rgb = led.to_d65(tile, cat);
+        assert!((rgb.r - rgb.g).abs() < 1e-4 && (rgb.g - rgb.b).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn transforms_cycle() {
+    assert_eq!(Cat::default().next().next(), Cat::default());
+    assert_ne!(Cat::Bradford.label(), Cat::Cat16.label());
+}
@@ -0,0 +1,87 @@
+//! Black/white calibration and its stored form.
+
+use colorpicky_core::calibration::{Calibration, CalibrationError};
+use colorpicky_core::sensor::{ExposureStatus, Metadata, Reading};
+
+fn reading(r: u16, g: u16, b: u16, c: u16, status: ExposureStatus) -> Reading {
+    Reading {
+        r,
+        g,
+        b,
+        c,
+        meta: Metadata {
+            integration_us: 100_000,
+            gain: 1,
+            full_scale: 43008,
+            status,
+            ..Metadata::default()
+        },
+        lux: None,
+        cct: None,
+    }
+}
+
+fn tiles() -> Calibration {
+    let black = reading(200, 220, 180, 600, ExposureStatus::Underexposed);
+    let white = reading(8000, 9000, 7000, 24000, ExposureStatus::Valid);
+    Calibration::new(&black, &white).unwrap()
+}
+
+#[test]
+fn reflectance_spans_black_to_white() {
+    let cal = tiles();
+    let black = cal.reflectance(&reading(200, 220, 180, 600, ExposureStatus::Valid));
+    let white = cal.reflectance(&reading(8000, 9000, 7000, 24000, ExposureStatus::Valid));
+    let grey = cal.reflectance(&reading(4100, 4610, 3590, 12300, ExposureStatus::Valid));
+    for i in 0..3 {
+        assert!(black[i].abs() < 1e-6);
+        assert!((white[i] - 1.0).abs() < 1e-6);
+        assert!((grey[i] - 0.5).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn clipped_or_flat_white_is_rejected() {
+    let black = reading(200, 220, 180, 600, ExposureStatus::Valid);
+    let clipped = reading(8000, 9000, 7000, 40000, ExposureStatus::Saturated);
+    assert_eq!(
+        Calibration::new(&black, &clipped),
+        Err(CalibrationError::WhiteSaturated)
+    );
+    let flat = reading(8000, 200, 7000, 24000, ExposureStatus::Valid);
+    assert_eq!(
+        Calibration::new(&black, &flat),
+        Err(CalibrationError::NoContrast)
+    );
+}
+
+#[test]
+fn bytes_round_trip() {
+    let cal = tiles();
+    assert_eq!(Calibration::from_bytes(&cal.to_bytes()), Ok(cal));
+}
+
+#[test]
+fn erased_or_damaged_storage_is_corrupt() {
+    // Erased flash reads back as all ones
+    let erased = [0xFF; Calibration::SIZE];
+    assert_eq!(
+        Calibration::from_bytes(&erased),
+        Err(CalibrationError::Corrupt)
+    );
+
+    let mut nan = tiles().to_bytes();
+    nan[4..8].copy_from_slice(&f32::NAN.to_le_bytes());
+    assert_eq!(
+        Calibration::from_bytes(&nan),
+        Err(CalibrationError::Corrupt)
+    );
+
+    // Black above white can't have come from `Calibration::new`
+    let mut swapped = tiles().to_bytes();
+    swapped[4..20].copy_from_slice(&tiles().to_bytes()[20..36]);
+    assert_eq!(
+        Calibration::from_bytes(&swapped),
+        Err(CalibrationError::Corrupt)
+    );
+}
@@ -0,0 +1,59 @@
+//! Color correction matrix fit.
+
+use colorpicky_core::ccm::{Fit, Matrix};
+
+const KNOWN: Matrix = Matrix {
+    m: [
+        [1.2, -0.15, -0.05, 0.01],
+        [-0.1, 1.1, 0.0, -0.02],
+        [0.05, -0.2, 1.3, 0.03],
+    ],
+};
+
+/// Deterministic patches spread over the unit cube.
+fn patches(n: usize) -> impl Iterator<Item = [f32; 3]> {
+    (0..n).map(|i| {
+        let i = i as f32;
+        [
+            (i * 0.37 + 0.1) % 1.0,
+            (i * 0.61 + 0.3) % 1.0,
+            (i * 0.83 + 0.7) % 1.0,
+        ]
+    })
+}
+
+#[test]
+fn recovers_a_known_matrix() {
+    let mut fit = Fit::new(true);
+    for measured in patches(24) {
+        fit.add(measured, KNOWN.apply(measured));
+    }
+    let matrix = fit.solve().unwrap();
+    for (row, known) in matrix.m.iter().zip(KNOWN.m) {
+        for (v, k) in row.iter().zip(known) {
+            assert!((v - k).abs() < 1e-4, "{:?}", matrix.m);
+        }
+    }
+}
+
+#[test]
+fn needs_as_many_patches_as_unknowns() {
+    let mut fit = Fit::new(true);
+    for measured in patches(3) {
+        fit.add(measured, KNOWN.apply(measured));
+    }
+    assert_eq!(fit.len(), 3);
+    assert_eq!(fit.solve(), None);
+}
+
+#[test]
+fn grays_alone_are_not_enough() {
+    for offset in [false, true] {
+        let mut fit = Fit::new(offset);
+        for i in 0..24 {
+            let v = (i as f32 + 1.0) / 25.0;
+            fit.add([v; 3], [v; 3]);
+        }
+        assert_eq!(fit.solve(), None, "offset {}", offset);
+    }
+}
@@ -0,0 +1,76 @@
+//! Color space conversions.
+
+use colorpicky_core::color_space::{Srgb, Xyz, D50, D65};
+
+/// Every 8-bit level in steps of 17 on each axis, plus a few out-of-gamut
+/// values that must survive unclamped.
+fn samples() -> impl Iterator<Item = Srgb> {
+    let levels = (0..=15).map(|i| i as f32 * 17.0 / 255.0);
+    let grid = levels.clone().flat_map(move |r| {
+        let levels = levels.clone();
+        levels
+            .clone()
+            .flat_map(move |g| levels.clone().map(move |b| Srgb::new(r, g, b)))
+    });
+    grid.chain([Srgb::new(1.1, -0.05, 0.5), Srgb::new(-0.1, 0.3, 1.05)])
+}
+
+fn assert_close(a: Srgb, b: Srgb, what: &str) {
+    let error = (a.r - b.r)
+        .abs()
+        .max((a.g - b.g).abs())
+        .max((a.b - b.b).abs());
+    assert!(error < 3e-5, "{}: {:?} came back as {:?}", what, a, b);
+}
+
+#[test]
+fn round_trips_through_every_space() {
+    for c in samples() {
+        assert_close(c, c.to_linear().to_srgb(), "linear");
+        assert_close(c, c.to_xyz().to_srgb(), "XYZ");
+        assert_close(c, c.to_lab().to_srgb(), "Lab");
+        assert_close(c, c.to_lab().to_lch().to_lab().to_srgb(), "LCh");
+        assert_close(c, c.to_oklab().to_srgb(), "Oklab");
+        assert_close(c, c.to_oklab().to_oklch().to_oklab().to_srgb(), "Oklch");
+    }
+}
+
+#[test]
+fn round_trips_through_device_spaces() {
+    // Hexcone and CMYK only make sense inside the gamut
+    for c in samples().take(16 * 16 * 16) {
+        assert_close(c, c.to_hsv().to_srgb(), "HSV");
+        assert_close(c, c.to_hsl().to_srgb(), "HSL");
+        assert_close(c, c.to_cmyk().to_srgb(), "CMYK");
+    }
+}
+
+#[test]
+fn hexcone_hues_follow_the_primaries() {
+    for (c, h) in [
+        (Srgb::new(1.0, 0.0, 0.0), 0.0),
+        (Srgb::new(1.0, 1.0, 0.0), 60.0),
+        (Srgb::new(0.0, 1.0, 0.0), 120.0),
+        (Srgb::new(0.0, 0.0, 1.0), 240.0),
+        (Srgb::new(1.0, 0.0, 0.5), 330.0),
+    ] {
+        assert!((c.to_hsv().h - h).abs() < 1e-3, "{:?}", c);
+        assert!((c.to_hsl().h - h).abs() < 1e-3, "{:?}", c);
+    }
+}
+
+#[test]
+fn d65_white_adapts_to_d50_white() {
+    let white = D65.d65_to_d50();
+    let error = |a: Xyz, b: Xyz| {
+        (a.x - b.x)
+            .abs()
+            .max((a.y - b.y).abs())
+            .max((a.z - b.z).abs())
+    };
+    assert!(error(white, D50) < 1e-4, "{:?}", white);
+
+    // So sRGB white is neutral in D50 Lab
+    let lab = Srgb::new(1.0, 1.0, 1.0).to_xyz().d65_to_d50().to_lab(D50);
+    assert!((lab.l - 100.0).abs() < 1e-2 && lab.a.abs() < 1e-2 && lab.b.abs() < 1e-2);
+}
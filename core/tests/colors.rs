@@ -0,0 +1,46 @@
+//! Palette matching.
+
+use embedded_graphics::pixelcolor::RgbColor;
+
+use colorpicky_core::colors::{match_colors, Matches, DEFAULT_MAX_DISTANCE, MAX_DISTANCES};
+use colorpicky_core::lab::DeltaE;
+use colorpicky_core::palette::PALETTES;
+
+#[test]
+fn palette_colors_match_themselves() {
+    let palette = PALETTES[0];
+    for entry in palette.entries {
+        let (r, g, b) = (entry.color.r(), entry.color.g(), entry.color.b());
+        for formula in [DeltaE::Cie76, DeltaE::Cie94, DeltaE::Ciede2000] {
+            let matches: Matches<3> = match_colors(palette, r, g, b, formula, MAX_DISTANCES[0]);
+            let best = matches.best().expect("an exact color is close");
+            assert_eq!(best.name, entry.name);
+            assert_eq!(best.distance, 0.0);
+        }
+    }
+}
+
+#[test]
+fn formulas_agree_on_what_is_close() {
+    // The threshold is scaled per formula, so switching formulas should
+    // rarely change whether a color gets a name.
+    let palette = PALETTES[0];
+    let max_distance = MAX_DISTANCES[DEFAULT_MAX_DISTANCE];
+    let (mut total, mut disagree) = (0, 0);
+    for r in (0..=255).step_by(15) {
+        for g in (0..=255).step_by(15) {
+            for b in (0..=255).step_by(15) {
+                let named = |formula| {
+                    let matches: Matches<1> = match_colors(palette, r, g, b, formula, max_distance);
+                    matches.best().is_some()
+                };
+                let reference = named(DeltaE::Ciede2000);
+                total += 1;
+                if named(DeltaE::Cie76) != reference || named(DeltaE::Cie94) != reference {
+                    disagree += 1;
+                }
+            }
+        }
+    }
+    assert!(disagree * 8 < total, "{} of {} disagree", disagree, total);
+}
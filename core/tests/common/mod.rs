@@ -0,0 +1,73 @@
+//! Shared test helpers: a simulated clock the sensor simulator reads and the
+//! delays advance, and a minimal executor for the async drivers.
+
+#![allow(dead_code)]
+
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+use colorpicky_core::tcs34725::sim::Tcs34725Sim;
+
+thread_local! {
+    static NOW_US: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Simulated time in microseconds. Each test runs on its own thread, so
+/// tests don't share a clock.
+pub fn now() -> u64 {
+    NOW_US.with(Cell::get)
+}
+
+pub fn advance(us: u64) {
+    NOW_US.with(|t| t.set(t.get() + us));
+}
+
+/// A simulator on the test clock.
+pub fn sim() -> Tcs34725Sim {
+    Tcs34725Sim::new().with_clock(now)
+}
+
+/// Sleeps by moving the test clock forward.
+pub struct Delay;
+
+impl embedded_hal::delay::DelayNs for Delay {
+    fn delay_ns(&mut self, ns: u32) {
+        advance(u64::from(ns).div_ceil(1000));
+    }
+}
+
+impl embedded_hal_async::delay::DelayNs for Delay {
+    async fn delay_ns(&mut self, ns: u32) {
+        advance(u64::from(ns).div_ceil(1000));
+    }
+}
+
+/// A delay that returns without time passing, so the sensor never
+/// finishes a cycle.
+pub struct Frozen;
+
+impl embedded_hal_async::delay::DelayNs for Frozen {
+    async fn delay_ns(&mut self, _ns: u32) {}
+}
+
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+/// Run a future to completion. Nothing here waits on real I/O, so every
+/// future is ready after a bounded number of polls.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
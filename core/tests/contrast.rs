@@ -0,0 +1,49 @@
+//! WCAG 2.x contrast ratio and APCA.
+
+use colorpicky_core::colors::NamedColor;
+use colorpicky_core::contrast::{apca_contrast, contrast_ratio, Contrast};
+use embedded_graphics::pixelcolor::Rgb888;
+
+const BLACK: Rgb888 = Rgb888::new(0, 0, 0);
+const WHITE: Rgb888 = Rgb888::new(255, 255, 255);
+const GRAY_76: Rgb888 = Rgb888::new(0x76, 0x76, 0x76);
+const GRAY_88: Rgb888 = Rgb888::new(0x88, 0x88, 0x88);
+
+fn named(color: Rgb888) -> NamedColor {
+    NamedColor {
+        name: "",
+        color,
+        description: None,
+    }
+}
+
+#[test]
+fn black_on_white_is_21_to_1() {
+    assert!((contrast_ratio(BLACK, WHITE) - 21.0).abs() < 1e-3);
+}
+
+#[test]
+fn gray_76_just_passes_aa() {
+    // The darkest gray commonly cited as passing AA on white
+    let c = Contrast::between(&named(GRAY_76), &named(WHITE));
+    assert!((c.ratio - 4.54).abs() < 0.01, "{}", c.ratio);
+    assert!(c.aa_normal() && c.aa_large());
+    assert!(!c.aaa_normal());
+}
+
+#[test]
+fn ratio_is_symmetric() {
+    for (a, b) in [(BLACK, WHITE), (GRAY_76, WHITE), (GRAY_88, BLACK)] {
+        assert_eq!(contrast_ratio(a, b), contrast_ratio(b, a));
+    }
+}
+
+#[test]
+fn apca_matches_the_reference_values() {
+    // From the APCA-W3 0.0.98G test values: #888 on #fff and the reverse
+    let dark_on_light = apca_contrast(GRAY_88, WHITE);
+    let light_on_dark = apca_contrast(WHITE, GRAY_88);
+    assert!((dark_on_light - 63.056).abs() < 0.05, "{}", dark_on_light);
+    assert!((light_on_dark + 68.541).abs() < 0.05, "{}", light_on_dark);
+    assert_eq!(apca_contrast(GRAY_88, GRAY_88), 0.0);
+}
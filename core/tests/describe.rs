@@ -0,0 +1,29 @@
+//! Descriptive color names.
+
+use colorpicky_core::color_space::Lab;
+use colorpicky_core::describe::{describe, MAX_LEN};
+
+fn name(r: u8, g: u8, b: u8) -> String {
+    describe(&Lab::from_srgb8(r, g, b)).to_string()
+}
+
+#[test]
+fn descriptions_fit_on_a_line() {
+    for r in (0..=255).step_by(5) {
+        for g in (0..=255).step_by(5) {
+            for b in (0..=255).step_by(5) {
+                let name = name(r, g, b);
+                assert!(name.len() <= MAX_LEN, "{:?} for {},{},{}", name, r, g, b);
+            }
+        }
+    }
+}
+
+#[test]
+fn names_the_basics() {
+    assert_eq!(name(0, 0, 0), "black");
+    assert_eq!(name(255, 255, 255), "white");
+    assert_eq!(name(128, 128, 128), "gray");
+    assert_eq!(name(0, 0, 255), "vivid dark blue");
+    assert_eq!(name(220, 200, 230), "grayish pale purple");
+}
@@ -0,0 +1,80 @@
+//! Auto exposure on both drivers, against the simulator.
+
+mod common;
+
+use colorpicky_core::exposure::{AutoExposure, ExposureStatus, LADDER};
+use colorpicky_core::tcs34725::sim::Light;
+use colorpicky_core::tcs34725::{asynch, Error, Gain, IntegrationTime, Tcs34725};
+use embedded_hal::i2c::ErrorKind;
+
+use common::{advance, block_on, sim, Delay};
+
+const GLARE: Light = Light::new(120.0, 60.0, 40.0, 200.0);
+const DIM: Light = Light::new(1.5, 1.0, 0.8, 3.0);
+/// Clips even at 2.4 ms and 1x.
+const SUN: Light = Light::new(900.0, 900.0, 900.0, 2000.0);
+
+/// Status of the final sample, and the settings the driver was left on.
+type Outcome = (
+    Result<ExposureStatus, Error<ErrorKind>>,
+    (IntegrationTime, Gain),
+);
+
+fn sync_read(light: Light) -> Outcome {
+    let mut tcs = Tcs34725::new(sim().step(0, light));
+    tcs.init(&mut Delay).unwrap();
+    advance(IntegrationTime::Ms50.micros() as u64);
+    let mut exposure = AutoExposure::new();
+    let result = exposure.read(&mut tcs, &mut Delay).map(|m| m.status);
+    (result, (tcs.integration_time(), tcs.gain()))
+}
+
+fn async_read(light: Light) -> Outcome {
+    let mut tcs = asynch::Tcs34725::new(sim().step(0, light));
+    block_on(tcs.init(&mut Delay)).unwrap();
+    let mut exposure = AutoExposure::new();
+    let result = block_on(exposure.read_async(&mut tcs, &mut Delay)).map(|m| m.status);
+    (result, (tcs.integration_time(), tcs.gain()))
+}
+
+#[test]
+fn bright_scenes_step_down_the_ladder() {
+    for read in [sync_read, async_read] {
+        let (status, settings) = read(GLARE);
+        assert_eq!(status.unwrap(), ExposureStatus::Valid);
+        assert_eq!(settings, LADDER[1]);
+    }
+}
+
+#[test]
+fn dim_scenes_step_up_the_ladder() {
+    for read in [sync_read, async_read] {
+        let (status, settings) = read(DIM);
+        assert_eq!(status.unwrap(), ExposureStatus::Valid);
+        assert_eq!(settings, LADDER[5]);
+    }
+}
+
+#[test]
+fn clipping_at_the_bottom_rung_is_an_error() {
+    for read in [sync_read, async_read] {
+        let (status, settings) = read(SUN);
+        assert!(matches!(status, Err(Error::Saturated)));
+        assert_eq!(settings, LADDER[0]);
+    }
+}
+
+#[test]
+fn exposure_carries_over_between_reads() {
+    let mut tcs = Tcs34725::new(sim().step(0, GLARE));
+    tcs.init(&mut Delay).unwrap();
+    advance(IntegrationTime::Ms50.micros() as u64);
+    let mut exposure = AutoExposure::new();
+    exposure.read(&mut tcs, &mut Delay).unwrap();
+
+    // Already on the right rung, so one sample is enough
+    advance(IntegrationTime::Ms24.micros() as u64);
+    let measurement = exposure.read(&mut tcs, &mut Delay).unwrap();
+    assert_eq!(measurement.status, ExposureStatus::Valid);
+    assert_eq!(exposure.settings(), LADDER[1]);
+}
@@ -0,0 +1,97 @@
+//! Color harmonies.
+
+use colorpicky_core::color_space::Srgb;
+use colorpicky_core::harmony::{generate, Scheme};
+
+const SCHEMES: [Scheme; 6] = [
+    Scheme::Complementary,
+    Scheme::SplitComplementary,
+    Scheme::Triadic,
+    Scheme::Tetradic,
+    Scheme::Analogous,
+    Scheme::Monochromatic,
+];
+
+const HALF_STEP: f32 = 0.5 / 255.0;
+
+/// Muted enough that every rotation fits in sRGB without losing chroma.
+fn muted() -> [Srgb; 3] {
+    [
+        Srgb::from_rgb8(170, 110, 100),
+        Srgb::from_rgb8(100, 140, 120),
+        Srgb::from_rgb8(110, 120, 170),
+    ]
+}
+
+fn hue(c: Srgb) -> f32 {
+    c.to_oklab().to_oklch().h
+}
+
+/// Signed hue difference folded into -180..180.
+fn hue_offset(from: f32, to: f32) -> f32 {
+    (to - from + 540.0) % 360.0 - 180.0
+}
+
+#[test]
+fn base_comes_first() {
+    for base in muted() {
+        for scheme in SCHEMES {
+            let first = generate(base, scheme)[0];
+            for (a, b) in [(first.r, base.r), (first.g, base.g), (first.b, base.b)] {
+                assert!(
+                    (a - b).abs() < 1e-3,
+                    "{:?}: {:?} vs {:?}",
+                    scheme,
+                    first,
+                    base
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn hues_are_offset_in_oklch() {
+    let cases: [(Scheme, &[f32]); 3] = [
+        (Scheme::Complementary, &[0.0, 180.0]),
+        (Scheme::Triadic, &[0.0, 120.0, -120.0]),
+        (Scheme::Analogous, &[0.0, -30.0, 30.0, -60.0, 60.0]),
+    ];
+    for base in muted() {
+        let h = hue(base);
+        for (scheme, offsets) in cases {
+            let colors = generate(base, scheme);
+            assert_eq!(colors.len(), offsets.len());
+            for (c, offset) in colors.iter().zip(offsets) {
+                let got = hue_offset(h, hue(*c));
+                let error = hue_offset(*offset, got).abs();
+                assert!(error < 0.5, "{:?}: {} instead of {}", scheme, got, offset);
+            }
+        }
+    }
+}
+
+#[test]
+fn colors_stay_in_gamut() {
+    let levels = [0, 60, 128, 200, 255];
+    for r in levels {
+        for g in levels {
+            for b in levels {
+                let base = Srgb::from_rgb8(r, g, b);
+                for scheme in SCHEMES {
+                    for c in generate(base, scheme) {
+                        // Within half an 8-bit step, so rounding lands in gamut
+                        for v in [c.r, c.g, c.b] {
+                            assert!(
+                                (-HALF_STEP..=1.0 + HALF_STEP).contains(&v),
+                                "{:?} from {:?}",
+                                c,
+                                base
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
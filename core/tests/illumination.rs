@@ -0,0 +1,154 @@
+//! LED-on minus LED-off reads.
+
+mod common;
+
+use std::cell::Cell;
+use std::convert::Infallible;
+use std::rc::Rc;
+
+use colorpicky_core::illumination::Illuminated;
+use colorpicky_core::sensor::{ColorSensor, Error, ExposureStatus, Metadata, Reading};
+use embedded_hal::digital::{ErrorType, OutputPin};
+
+use common::block_on;
+
+struct Led(Rc<Cell<bool>>);
+
+impl ErrorType for Led {
+    type Error = Infallible;
+}
+
+impl OutputPin for Led {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.0.set(false);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.0.set(true);
+        Ok(())
+    }
+}
+
+/// Reads `lit` counts with the LED on and `ambient` with it off. Like auto
+/// exposure, `read()` re-ranges for the darker scene (4x the integration
+/// time, status Valid); `read_fixed()` keeps the last settings.
+struct FakeSensor {
+    led: Rc<Cell<bool>>,
+    lit: [u16; 4],
+    ambient: [u16; 4],
+    lit_status: ExposureStatus,
+}
+
+impl FakeSensor {
+    fn reading(&self, integration_us: u32, status: ExposureStatus) -> Reading {
+        let [r, g, b, c] = if self.led.get() {
+            self.lit
+        } else {
+            self.ambient
+        };
+        Reading {
+            r,
+            g,
+            b,
+            c,
+            meta: Metadata {
+                integration_us,
+                gain: 4,
+                full_scale: 21504,
+                status,
+                ..Metadata::default()
+            },
+            lux: Some(if self.led.get() { 900.0 } else { 120.0 }),
+            cct: None,
+        }
+    }
+}
+
+impl ColorSensor for FakeSensor {
+    type BusError = Infallible;
+
+    async fn init(&mut self) -> Result<(), Error<Infallible>> {
+        Ok(())
+    }
+
+    async fn read(&mut self) -> Result<Reading, Error<Infallible>> {
+        Ok(if self.led.get() {
+            self.reading(50_400, self.lit_status)
+        } else {
+            let mut reading = self.reading(201_600, ExposureStatus::Valid);
+            reading.r *= 4;
+            reading.g *= 4;
+            reading.b *= 4;
+            reading.c *= 4;
+            reading
+        })
+    }
+
+    async fn read_fixed(&mut self) -> Result<Reading, Error<Infallible>> {
+        Ok(self.reading(50_400, ExposureStatus::Underexposed))
+    }
+
+    async fn sleep(&mut self) -> Result<(), Error<Infallible>> {
+        Ok(())
+    }
+
+    async fn wake(&mut self) -> Result<(), Error<Infallible>> {
+        Ok(())
+    }
+
+    async fn wait_for_change(&mut self) -> Result<(), Error<Infallible>> {
+        Ok(())
+    }
+}
+
+fn illuminated(lit_status: ExposureStatus) -> Illuminated<FakeSensor, Led> {
+    let led = Rc::new(Cell::new(false));
+    let sensor = FakeSensor {
+        led: led.clone(),
+        lit: [6000, 5000, 4000, 15000],
+        ambient: [600, 500, 300, 1500],
+        lit_status,
+    };
+    Illuminated::new(sensor, Led(led))
+}
+
+#[test]
+fn differential_subtracts_raw_counts_at_the_lit_settings() {
+    let mut sensor = illuminated(ExposureStatus::Valid);
+    let reading = block_on(sensor.read()).unwrap();
+    assert_eq!(
+        (reading.r, reading.g, reading.b, reading.c),
+        (5400, 4500, 3700, 13500)
+    );
+    assert_eq!(reading.meta.integration_us, 50_400);
+    assert_eq!(reading.meta.ambient, Some(0.1));
+    // Room light for the light meter values
+    assert_eq!(reading.lux, Some(120.0));
+}
+
+#[test]
+fn differential_keeps_the_lit_status() {
+    // The dark read alone would be Underexposed
+    let mut sensor = illuminated(ExposureStatus::Valid);
+    assert_eq!(
+        block_on(sensor.read()).unwrap().meta.status,
+        ExposureStatus::Valid
+    );
+
+    let mut sensor = illuminated(ExposureStatus::Saturated);
+    assert_eq!(
+        block_on(sensor.read()).unwrap().meta.status,
+        ExposureStatus::Saturated
+    );
+}
+
+#[test]
+fn plain_mode_reads_ambient_with_auto_exposure() {
+    let mut sensor = illuminated(ExposureStatus::Valid);
+    sensor.set_differential(false);
+    let reading = block_on(sensor.read()).unwrap();
+    assert_eq!(reading.c, 6000);
+    assert_eq!(reading.meta.integration_us, 201_600);
+    assert_eq!(reading.meta.ambient, None);
+}
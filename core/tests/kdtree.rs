@@ -0,0 +1,98 @@
+//! The palette search tree against a linear scan.
+
+use colorpicky_core::color_space::Lab;
+use colorpicky_core::kdtree::{self, Nearest, Node};
+use colorpicky_core::lab::DeltaE;
+use colorpicky_core::palette::PALETTES;
+
+const FORMULAS: [DeltaE; 3] = [DeltaE::Cie76, DeltaE::Cie94, DeltaE::Ciede2000];
+
+/// Matches per lookup, as many as the firmware asks for.
+const N: usize = 3;
+
+fn linear(labs: &[Lab], sample: &Lab, formula: DeltaE) -> Nearest<N> {
+    let mut found = Nearest::new();
+    for (i, lab) in labs.iter().enumerate() {
+        found.push(i as u16, formula.distance(lab, sample));
+    }
+    found
+}
+
+fn assert_same(nodes: &[Node], labs: &[Lab], samples: &[Lab], what: &str) {
+    for formula in FORMULAS {
+        for sample in samples {
+            let indexed = kdtree::nearest::<N>(nodes, |i| labs[i], sample, formula);
+            assert_eq!(
+                indexed.as_slice(),
+                linear(labs, sample, formula).as_slice(),
+                "{}: {:?} with {:?}",
+                what,
+                sample,
+                formula
+            );
+        }
+    }
+}
+
+fn build(labs: &[Lab]) -> Vec<Node> {
+    let mut order: Vec<u16> = (0..labs.len() as u16).collect();
+    let mut nodes = vec![
+        Node {
+            entry: 0,
+            axis: 0,
+            min: [0.0; 3],
+            max: [0.0; 3],
+            max_chroma: 0.0,
+        };
+        labs.len()
+    ];
+    kdtree::build(labs, &mut order, &mut nodes);
+    nodes
+}
+
+/// Deterministic values in `lo..hi`.
+fn scatter(seed: &mut u32, lo: f32, hi: f32) -> f32 {
+    *seed ^= *seed << 13;
+    *seed ^= *seed >> 17;
+    *seed ^= *seed << 5;
+    lo + (hi - lo) * (*seed as f32 / u32::MAX as f32)
+}
+
+#[test]
+fn palettes_match_a_linear_scan() {
+    // A grid of the sRGB cube, plus the entries' own colors where ties are
+    // most likely
+    let grid = (0..=255).step_by(17);
+    let mut samples = Vec::new();
+    for r in grid.clone() {
+        for g in grid.clone() {
+            for b in grid.clone() {
+                samples.push(Lab::from_srgb8(r, g, b));
+            }
+        }
+    }
+
+    for palette in PALETTES {
+        let labs: Vec<Lab> = palette.entries.iter().map(|e| e.lab).collect();
+        let mut samples = samples.clone();
+        samples.extend_from_slice(&labs);
+        assert_same(palette.index, &labs, &samples, palette.name);
+    }
+}
+
+#[test]
+fn ciede2000_bound_holds_around_blue() {
+    // CIEDE2000 is not a metric: near hue 275° the rotation term can make
+    // a color nearer than its box suggests. Pack entries and samples
+    // around there so the bound is what decides each pruning.
+    let mut seed = 0x2545_f491;
+    let blue = |seed: &mut u32| {
+        let l = scatter(seed, 10.0, 70.0);
+        let c = scatter(seed, 5.0, 80.0);
+        let h = scatter(seed, 230.0, 320.0).to_radians();
+        Lab::new(l, c * h.cos(), c * h.sin())
+    };
+    let labs: Vec<Lab> = (0..400).map(|_| blue(&mut seed)).collect();
+    let samples: Vec<Lab> = (0..2000).map(|_| blue(&mut seed)).collect();
+    assert_same(&build(&labs), &labs, &samples, "blue");
+}
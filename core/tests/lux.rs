@@ -0,0 +1,71 @@
+//! DN40 lux and color temperature.
+
+mod common;
+
+use colorpicky_core::lux;
+use colorpicky_core::tcs34725::sim::Light;
+use colorpicky_core::tcs34725::{Gain, IntegrationTime, Rgbc, Tcs34725};
+
+use common::{advance, sim, Delay};
+
+#[test]
+fn dn40_worked_example() {
+    let rgbc = Rgbc {
+        r: 1300,
+        g: 1100,
+        b: 800,
+        c: 3000,
+        integration_time: IntegrationTime::Ms154,
+        gain: Gain::X4,
+    };
+    // IR = (1300 + 1100 + 800 - 3000) / 2 = 100, so R' G' B' = 1200 1000 700
+    // G'' = 0.136 * 1200 + 1000 - 0.444 * 700 = 852.4
+    // CPL = 153.6 ms * 4 / (GA 1 * DF 310) = 1.98194
+    // lux = 852.4 / 1.98194 = 430.08
+    // CCT = 3810 * 700 / 1200 + 1391 = 3613.5 K
+    let light = lux::measure(&rgbc);
+    assert!((light.lux - 430.08).abs() < 0.01, "{}", light.lux);
+    assert!((light.cct.unwrap() - 3613.5).abs() < 0.01);
+}
+
+#[test]
+fn no_red_no_color_temperature() {
+    let rgbc = Rgbc {
+        r: 0,
+        g: 500,
+        b: 500,
+        c: 1000,
+        integration_time: IntegrationTime::Ms24,
+        gain: Gain::X1,
+    };
+    assert_eq!(lux::cct(&rgbc), None);
+}
+
+#[test]
+fn lux_does_not_depend_on_the_settings() {
+    // Warm light with some IR: R + G + B exceeds C
+    let mut tcs = Tcs34725::new(sim().step(0, Light::new(30.0, 12.0, 6.0, 40.0)));
+    tcs.init(&mut Delay).unwrap();
+
+    let mut readings = Vec::new();
+    for (integration_time, gain) in [
+        (IntegrationTime::Ms24, Gain::X1),
+        (IntegrationTime::Ms101, Gain::X4),
+        (IntegrationTime::Ms154, Gain::X4),
+        (IntegrationTime::Ms700, Gain::X1),
+    ] {
+        tcs.set_integration_time(integration_time).unwrap();
+        tcs.set_gain(gain).unwrap();
+        // Let a full cycle run with the new settings
+        advance(2 * IntegrationTime::Ms700.micros() as u64);
+        let rgbc = tcs.read_all().unwrap();
+        readings.push(lux::measure(&rgbc));
+    }
+
+    let first = readings[0];
+    for light in &readings[1..] {
+        assert!((light.lux / first.lux - 1.0).abs() < 0.02, "{:?}", readings);
+        let (cct, first_cct) = (light.cct.unwrap(), first.cct.unwrap());
+        assert!((cct / first_cct - 1.0).abs() < 0.02, "{:?}", readings);
+    }
+}
@@ -0,0 +1,35 @@
+//! Paint-mixing recipes.
+
+use colorpicky_core::lab::DeltaE;
+use colorpicky_core::mixing::{recipe, Part, MAX_PARTS};
+use embedded_graphics::pixelcolor::{Rgb888, RgbColor};
+
+const WHITE: Rgb888 = Rgb888::new(245, 245, 240);
+const BLUE: Rgb888 = Rgb888::new(20, 40, 160);
+const YELLOW: Rgb888 = Rgb888::new(250, 210, 20);
+const RED: Rgb888 = Rgb888::new(200, 30, 30);
+
+#[test]
+fn no_paints_no_recipe() {
+    assert_eq!(recipe(RED, &[], DeltaE::Ciede2000), None);
+}
+
+#[test]
+fn a_saved_paint_is_used_alone() {
+    let paints = [WHITE, BLUE, YELLOW, RED];
+    let found = recipe(RED, &paints, DeltaE::Ciede2000).unwrap();
+    assert_eq!(found.parts.as_slice(), &[Part { paint: 3, parts: 1 }]);
+    assert!(found.delta_e < 0.5, "{}", found.delta_e);
+}
+
+#[test]
+fn blue_and_yellow_make_green() {
+    let paints = [WHITE, BLUE, YELLOW, RED];
+    let green = Rgb888::new(40, 110, 50);
+    let found = recipe(green, &paints, DeltaE::Ciede2000).unwrap();
+    let used: Vec<usize> = found.parts.iter().map(|p| p.paint).collect();
+    assert!(used.contains(&1) && used.contains(&2), "{:?}", found.parts);
+    assert!(found.color.g() > found.color.r() && found.color.g() > found.color.b());
+    let total: u8 = found.parts.iter().map(|p| p.parts).sum();
+    assert!(total <= MAX_PARTS);
+}
@@ -0,0 +1,50 @@
+//! DN40 IR removal and saturation flags.
+
+use colorpicky_core::processing::{self, Saturation};
+use colorpicky_core::tcs34725::{Gain, IntegrationTime, Rgbc};
+
+fn rgbc(integration_time: IntegrationTime, r: u16, g: u16, b: u16, c: u16) -> Rgbc {
+    Rgbc {
+        r,
+        g,
+        b,
+        c,
+        integration_time,
+        gain: Gain::X1,
+    }
+}
+
+#[test]
+fn ir_comes_off_every_channel() {
+    let corrected = processing::process(&rgbc(IntegrationTime::Ms154, 600, 500, 400, 1300));
+    assert_eq!(corrected.ir, 100);
+    assert_eq!(
+        (corrected.r, corrected.g, corrected.b, corrected.c),
+        (500, 400, 300, 1200)
+    );
+    assert_eq!(corrected.saturation, Saturation::None);
+}
+
+#[test]
+fn full_scale_is_analog_saturation() {
+    let max = IntegrationTime::Ms154.max_count();
+    let corrected = processing::process(&rgbc(IntegrationTime::Ms154, 1, 1, 1, max));
+    assert_eq!(corrected.saturation, Saturation::Analog);
+    assert!(corrected.saturation.is_saturated());
+}
+
+#[test]
+fn short_atime_flags_the_ripple_band() {
+    let max = IntegrationTime::Ms24.max_count();
+    let c = max - max / 8;
+    assert_eq!(
+        processing::saturation(&rgbc(IntegrationTime::Ms24, 1, 1, 1, c)),
+        Saturation::Ripple
+    );
+    // Long ATIMEs don't ripple
+    let max = IntegrationTime::Ms700.max_count();
+    assert_eq!(
+        processing::saturation(&rgbc(IntegrationTime::Ms700, 1, 1, 1, max - max / 8)),
+        Saturation::None
+    );
+}
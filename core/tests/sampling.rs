@@ -0,0 +1,83 @@
+//! Burst averaging with outlier rejection.
+
+use colorpicky_core::sampling::filter;
+use colorpicky_core::sensor::{Metadata, Reading};
+
+fn reading(r: u16, g: u16, b: u16, c: u16) -> Reading {
+    Reading {
+        r,
+        g,
+        b,
+        c,
+        meta: Metadata {
+            integration_us: 100_000,
+            gain: 1,
+            full_scale: 43008,
+            ..Metadata::default()
+        },
+        lux: None,
+        cct: None,
+    }
+}
+
+fn counts(r: &Reading) -> [u16; 4] {
+    [r.r, r.g, r.b, r.c]
+}
+
+#[test]
+fn empty_burst_has_no_result() {
+    assert!(filter(&[]).is_none());
+}
+
+#[test]
+fn identical_burst_keeps_everything() {
+    let burst = [reading(1000, 2000, 1500, 4000); 5];
+    let filtered = filter(&burst).unwrap();
+    assert_eq!(counts(&filtered.reading), [1000, 2000, 1500, 4000]);
+    assert_eq!(filtered.stats.used, 5);
+    assert_eq!(filtered.stats.rejected, 0);
+    assert_eq!(filtered.stats.std_dev, [0.0; 4]);
+    assert_eq!(filtered.stats.spread_percent(), 0.0);
+}
+
+#[test]
+fn a_spike_is_rejected() {
+    let mut burst = [reading(1000, 2000, 1500, 4000); 5];
+    burst[2] = reading(3000, 2000, 1500, 6000);
+    let filtered = filter(&burst).unwrap();
+    assert_eq!(counts(&filtered.reading), [1000, 2000, 1500, 4000]);
+    assert_eq!(filtered.stats.used, 4);
+    assert_eq!(filtered.stats.rejected, 1);
+}
+
+#[test]
+fn even_burst_averages_around_the_middle_pair() {
+    // The median (1003) is between two samples; all four are close to it
+    let burst = [
+        reading(1000, 2000, 1500, 4000),
+        reading(1002, 2002, 1502, 4002),
+        reading(1004, 2004, 1504, 4004),
+        reading(1006, 2006, 1506, 4006),
+    ];
+    let filtered = filter(&burst).unwrap();
+    assert!((filtered.stats.median[0] - 10.03).abs() < 1e-4);
+    assert_eq!(filtered.stats.used, 4);
+    assert_eq!(counts(&filtered.reading), [1003, 2003, 1503, 4003]);
+    assert!(filtered.stats.spread_percent() > 0.0);
+}
+
+#[test]
+fn no_inliers_falls_back_to_the_median() {
+    // Each sample spikes a different channel, so each is an outlier somewhere
+    let base = reading(1000, 2000, 1500, 4000);
+    let burst = [
+        Reading { r: 5000, ..base },
+        Reading { g: 9000, ..base },
+        Reading { b: 7000, ..base },
+        Reading { c: 20000, ..base },
+    ];
+    let filtered = filter(&burst).unwrap();
+    assert_eq!(filtered.stats.used, 0);
+    assert_eq!(filtered.stats.rejected, 4);
+    assert_eq!(counts(&filtered.reading), [1000, 2000, 1500, 4000]);
+}
@@ -0,0 +1,391 @@
+//! Both TCS34725 drivers against the register-level simulator.
+
+mod common;
+
+use colorpicky_core::exposure::ExposureStatus;
+use colorpicky_core::sensor::ColorSensor;
+use colorpicky_core::tcs34725::color_sensor::AutoTcs34725;
+use colorpicky_core::tcs34725::sim::{Light, NoInt};
+use colorpicky_core::tcs34725::*;
+use embedded_hal::digital::{ErrorKind, ErrorType};
+use embedded_hal_async::digital::Wait;
+
+use common::{advance, block_on, now, sim, Delay, Frozen};
+
+const ORANGE: Light = Light::new(30.0, 12.0, 6.0, 40.0);
+const TEAL: Light = Light::new(5.0, 20.0, 18.0, 36.0);
+
+/// One integration cycle at the default settings. `init()` waits out the
+/// warm-up, so the first sample is due one cycle later.
+const CYCLE: u64 = DEFAULT_INTEGRATION_TIME.micros() as u64;
+
+/// An INT line whose GPIO always fails.
+struct BrokenPin;
+
+impl ErrorType for BrokenPin {
+    type Error = ErrorKind;
+}
+
+impl Wait for BrokenPin {
+    async fn wait_for_high(&mut self) -> Result<(), ErrorKind> {
+        Err(ErrorKind::Other)
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), ErrorKind> {
+        Err(ErrorKind::Other)
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), ErrorKind> {
+        Err(ErrorKind::Other)
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), ErrorKind> {
+        Err(ErrorKind::Other)
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), ErrorKind> {
+        Err(ErrorKind::Other)
+    }
+}
+
+fn assert_light(rgbc: &Rgbc, light: Light) {
+    let [r, g, b, c] = rgbc.normalized();
+    for (got, want) in [(r, light.r), (g, light.g), (b, light.b), (c, light.c)] {
+        assert!((got - want).abs() < 0.1, "{rgbc:?} is not {light:?}");
+    }
+}
+
+#[test]
+fn init_accepts_both_part_ids() {
+    for id in [ID_TCS34725, ID_TCS34727] {
+        let mut sim = sim().with_id(id);
+        assert!(Tcs34725::new(&mut sim).init(&mut Delay).is_ok());
+        assert!(block_on(asynch::Tcs34725::new(&mut sim).init(&mut Delay)).is_ok());
+    }
+}
+
+#[test]
+fn init_rejects_other_ids() {
+    let mut sim = sim().with_id(0x12);
+    assert!(matches!(
+        Tcs34725::new(&mut sim).init(&mut Delay),
+        Err(Error::UnexpectedId(0x12))
+    ));
+    assert!(matches!(
+        block_on(asynch::Tcs34725::new(&mut sim).init(&mut Delay)),
+        Err(Error::UnexpectedId(0x12))
+    ));
+    // Nothing is powered up after a failed check
+    assert_eq!(sim.register(REG_ENABLE), 0);
+}
+
+#[test]
+fn init_writes_atime_and_control() {
+    let mut sim = sim();
+    Tcs34725::new(&mut sim).init(&mut Delay).unwrap();
+    assert_eq!(sim.register(REG_ATIME), DEFAULT_INTEGRATION_TIME.atime());
+    assert_eq!(sim.register(REG_CONTROL), DEFAULT_GAIN.bits());
+    assert_eq!(sim.register(REG_ENABLE), MASK_ENABLE_PON | MASK_ENABLE_AEN);
+
+    let mut sim = common::sim();
+    block_on(asynch::Tcs34725::new(&mut sim).init(&mut Delay)).unwrap();
+    assert_eq!(sim.register(REG_ATIME), DEFAULT_INTEGRATION_TIME.atime());
+    assert_eq!(sim.register(REG_CONTROL), DEFAULT_GAIN.bits());
+}
+
+#[test]
+fn settings_reach_the_registers() {
+    let mut sim = sim();
+    let mut tcs = Tcs34725::new(&mut sim);
+    tcs.init(&mut Delay).unwrap();
+    tcs.set_integration_time(IntegrationTime::Ms154).unwrap();
+    tcs.set_gain(Gain::X60).unwrap();
+    tcs.set_wait_time(WaitTime::from_millis(1000)).unwrap();
+    tcs.set_persistence(Persistence::Cycles5).unwrap();
+    assert_eq!(tcs.integration_time(), IntegrationTime::Ms154);
+    assert_eq!(tcs.gain(), Gain::X60);
+
+    assert_eq!(sim.register(REG_ATIME), IntegrationTime::Ms154.atime());
+    assert_eq!(sim.register(REG_CONTROL), Gain::X60.bits());
+    assert_eq!(sim.register(REG_WTIME), WaitTime::from_millis(1000).wtime());
+    assert_eq!(sim.register(REG_CONFIG), MASK_CONFIG_WLONG);
+    assert_eq!(sim.register(REG_PERS), Persistence::Cycles5.bits());
+}
+
+#[test]
+fn read_all_is_not_ready_until_a_cycle_completes() {
+    let mut tcs = Tcs34725::new(sim().step(0, ORANGE));
+    tcs.init(&mut Delay).unwrap();
+    assert!(matches!(tcs.read_all(), Err(Error::NotReady)));
+
+    advance(CYCLE - 100);
+    assert!(matches!(tcs.read_all(), Err(Error::NotReady)));
+
+    advance(100);
+    assert_light(&tcs.read_all().unwrap(), ORANGE);
+}
+
+#[test]
+fn read_all_scales_with_integration_time_and_gain() {
+    let mut tcs = Tcs34725::new(sim().step(0, ORANGE));
+    tcs.init(&mut Delay).unwrap();
+    advance(CYCLE);
+    let rgbc = tcs.read_all().unwrap();
+    // 50.4 ms at 4x: 201.6 counts per unit of light
+    assert_eq!((rgbc.c, rgbc.r, rgbc.g, rgbc.b), (8064, 6048, 2419, 1209));
+
+    // New settings only show once a cycle has run with them
+    tcs.set_integration_time(IntegrationTime::Ms24).unwrap();
+    tcs.set_gain(Gain::X1).unwrap();
+    advance((2 * IntegrationTime::Ms24.micros()) as u64);
+    let rgbc = tcs.read_all().unwrap();
+    assert_eq!(rgbc.c, 960);
+    assert_light(&rgbc, ORANGE);
+}
+
+#[test]
+fn longer_integration_takes_effect() {
+    let mut tcs = Tcs34725::new(sim().step(0, ORANGE));
+    tcs.init(&mut Delay).unwrap();
+    tcs.set_integration_time(IntegrationTime::Ms2_4).unwrap();
+    advance(CYCLE * 10);
+    assert_light(&tcs.read_all().unwrap(), ORANGE);
+
+    // Many short cycles have run; the longer ones must still be counted
+    tcs.set_integration_time(IntegrationTime::Ms154).unwrap();
+    advance((2 * IntegrationTime::Ms154.micros()) as u64);
+    let rgbc = tcs.read_all().unwrap();
+    // 153.6 ms at 4x: 614.4 counts per unit of light
+    assert_eq!(rgbc.c, 24576);
+    assert_light(&rgbc, ORANGE);
+}
+
+#[test]
+fn read_all_clips_at_full_scale() {
+    let mut tcs = Tcs34725::new(sim().step(0, Light::new(500.0, 500.0, 500.0, 900.0)));
+    tcs.init(&mut Delay).unwrap();
+    advance(CYCLE);
+    let rgbc = tcs.read_all().unwrap();
+    assert_eq!(rgbc.c, DEFAULT_INTEGRATION_TIME.max_count());
+}
+
+#[test]
+fn scripted_light_changes_show_up_cycle_by_cycle() {
+    let mut tcs = Tcs34725::new(sim().step(0, ORANGE).step(200_000, TEAL).repeat(400_000));
+    tcs.init(&mut Delay).unwrap();
+    advance(CYCLE);
+    assert_light(&tcs.read_all().unwrap(), ORANGE);
+
+    advance(200_000);
+    assert_light(&tcs.read_all().unwrap(), TEAL);
+
+    // Back to the first step once the script wraps
+    advance(200_000);
+    assert_light(&tcs.read_all().unwrap(), ORANGE);
+}
+
+#[test]
+fn sleep_stops_cycles_and_wake_restarts_them() {
+    let mut tcs = Tcs34725::new(sim().step(0, ORANGE));
+    tcs.init(&mut Delay).unwrap();
+    tcs.sleep().unwrap();
+    assert_eq!(tcs.power_state(), PowerState::Sleep);
+    advance(CYCLE);
+    assert!(matches!(tcs.read_all(), Err(Error::NotReady)));
+
+    // Waking waits out the warm-up, so one integration time is enough
+    tcs.wake(&mut Delay).unwrap();
+    advance(CYCLE);
+    assert_light(&tcs.read_all().unwrap(), ORANGE);
+}
+
+#[test]
+fn both_drivers_wait_out_the_warm_up() {
+    let mut tcs = Tcs34725::new(sim());
+    tcs.init(&mut Delay).unwrap();
+    tcs.sleep().unwrap();
+    let start = now();
+    tcs.enable(&mut Delay).unwrap();
+    assert_eq!(now() - start, WARMUP_US as u64);
+    // Already powered: nothing to wait for
+    tcs.enable(&mut Delay).unwrap();
+    assert_eq!(now() - start, WARMUP_US as u64);
+
+    let mut tcs = asynch::Tcs34725::new(sim());
+    block_on(tcs.init(&mut Delay)).unwrap();
+    block_on(tcs.sleep()).unwrap();
+    let start = now();
+    block_on(tcs.enable(&mut Delay)).unwrap();
+    assert_eq!(now() - start, WARMUP_US as u64);
+    block_on(tcs.enable(&mut Delay)).unwrap();
+    assert_eq!(now() - start, WARMUP_US as u64);
+}
+
+#[test]
+fn wait_time_rounds_up_and_clamps() {
+    assert_eq!(WaitTime::from_millis(0).micros(), CYCLE_US);
+    assert_eq!(WaitTime::from_millis(100).micros(), 42 * CYCLE_US);
+    assert!(!WaitTime::from_millis(614).is_long());
+    assert!(WaitTime::from_millis(615).is_long());
+    assert_eq!(WaitTime::from_millis(u32::MAX), WaitTime::MAX);
+}
+
+#[test]
+fn async_read_all_waits_for_a_fresh_cycle() {
+    let mut tcs = asynch::Tcs34725::new(sim().step(0, ORANGE).step(100_000, TEAL));
+    block_on(tcs.init(&mut Delay)).unwrap();
+
+    let start = now();
+    assert_light(&block_on(tcs.read_all(&mut Delay)).unwrap(), ORANGE);
+    assert!(now() - start >= DEFAULT_INTEGRATION_TIME.micros() as u64);
+
+    // The read restarts integration, so it never returns the stale sample
+    advance(100_000);
+    assert_light(&block_on(tcs.read_all(&mut Delay)).unwrap(), TEAL);
+}
+
+#[test]
+fn async_read_all_covers_the_wait_time() {
+    let mut tcs = asynch::Tcs34725::new(sim().step(0, ORANGE));
+    block_on(tcs.init(&mut Delay)).unwrap();
+    block_on(tcs.set_wait_time(WaitTime::from_millis(100))).unwrap();
+    block_on(tcs.set_wait_enabled(true)).unwrap();
+    assert_light(&block_on(tcs.read_all(&mut Delay)).unwrap(), ORANGE);
+}
+
+#[test]
+fn async_read_all_gives_up_without_avalid() {
+    let mut tcs = asynch::Tcs34725::new(sim().step(0, ORANGE));
+    block_on(tcs.init(&mut Delay)).unwrap();
+    assert!(matches!(
+        block_on(tcs.read_all(&mut Frozen)),
+        Err(Error::NotReady)
+    ));
+}
+
+#[test]
+fn interrupt_asserts_after_persistence_outside_the_window() {
+    let mut sim = sim().step(0, ORANGE);
+    let mut tcs = Tcs34725::new(&mut sim);
+    tcs.init(&mut Delay).unwrap();
+    tcs.set_persistence(Persistence::Cycles2).unwrap();
+    tcs.set_interrupt_enabled(true).unwrap();
+
+    // 8064 counts sits inside the window
+    tcs.set_interrupt_thresholds(7000, 9000).unwrap();
+    advance(CYCLE * 4);
+    assert!(!tcs.interrupt_pending().unwrap());
+
+    tcs.set_interrupt_thresholds(100, 1000).unwrap();
+    advance(CYCLE);
+    assert!(!tcs.interrupt_pending().unwrap());
+    advance(CYCLE);
+    assert!(tcs.interrupt_pending().unwrap());
+    assert!(sim.int_asserted());
+
+    let mut tcs = Tcs34725::new(&mut sim);
+    tcs.clear_interrupt().unwrap();
+    assert!(!tcs.interrupt_pending().unwrap());
+}
+
+#[test]
+fn every_cycle_persistence_ignores_the_window() {
+    let mut sim = sim().step(0, ORANGE);
+    let mut tcs = Tcs34725::new(&mut sim);
+    tcs.init(&mut Delay).unwrap();
+    tcs.set_persistence(Persistence::EveryCycle).unwrap();
+    tcs.set_interrupt_enabled(true).unwrap();
+
+    // 8064 counts sits inside the window, but every cycle still asserts
+    tcs.set_interrupt_thresholds(7000, 9000).unwrap();
+    tcs.clear_interrupt().unwrap();
+    advance(CYCLE);
+    assert!(tcs.interrupt_pending().unwrap());
+
+    tcs.clear_interrupt().unwrap();
+    assert!(!tcs.interrupt_pending().unwrap());
+    advance(CYCLE);
+    assert!(tcs.interrupt_pending().unwrap());
+    assert!(sim.int_asserted());
+}
+
+#[test]
+fn wait_for_interrupt_clears_the_interrupt() {
+    let mut sim = sim().step(0, ORANGE);
+    let mut tcs = asynch::Tcs34725::new(&mut sim);
+    block_on(tcs.init(&mut Delay)).unwrap();
+    block_on(tcs.set_persistence(Persistence::Cycles1)).unwrap();
+    block_on(tcs.set_interrupt_thresholds(100, 1000)).unwrap();
+    block_on(tcs.set_interrupt_enabled(true)).unwrap();
+    advance(CYCLE);
+    assert!(block_on(tcs.interrupt_pending()).unwrap());
+
+    block_on(tcs.wait_for_interrupt(&mut NoInt)).unwrap();
+    assert!(!block_on(tcs.interrupt_pending()).unwrap());
+    assert!(!sim.int_asserted());
+}
+
+#[test]
+fn int_pin_errors_are_reported() {
+    let mut tcs = asynch::Tcs34725::new(sim());
+    block_on(tcs.init(&mut Delay)).unwrap();
+    assert!(matches!(
+        block_on(tcs.wait_for_interrupt(&mut BrokenPin)),
+        Err(Error::Pin)
+    ));
+
+    let mut sensor = AutoTcs34725::new(sim(), BrokenPin, Delay);
+    block_on(sensor.init()).unwrap();
+    assert!(matches!(
+        block_on(sensor.wait_for_change()),
+        Err(Error::Pin)
+    ));
+}
+
+#[test]
+fn auto_sensor_reports_ir_corrected_light() {
+    let mut sensor = AutoTcs34725::new(sim().step(0, ORANGE), NoInt, Delay);
+    block_on(sensor.init()).unwrap();
+    let reading = block_on(sensor.read()).unwrap();
+
+    assert_eq!(reading.meta.status, ExposureStatus::Valid);
+    assert_eq!(
+        reading.meta.integration_us,
+        DEFAULT_INTEGRATION_TIME.micros()
+    );
+    assert!(reading.lux.is_some());
+    // IR removal takes the same amount off every channel
+    assert!(reading.r > reading.g && reading.g > reading.b);
+    assert!(reading.c < 8064);
+}
+
+#[test]
+fn auto_sensor_flags_clipped_readings() {
+    // Still above 70% of full scale at 2.4 ms once the ladder runs out
+    let glare = Light::new(250.0, 250.0, 250.0, 420.0);
+    let mut sensor = AutoTcs34725::new(sim().step(0, glare), NoInt, Delay);
+    block_on(sensor.init()).unwrap();
+    let reading = block_on(sensor.read()).unwrap();
+    assert_eq!(reading.meta.status, ExposureStatus::Saturated);
+    assert!(!reading.is_valid());
+}
+
+#[test]
+fn auto_sensor_fixed_reads_keep_the_settings() {
+    let script = sim()
+        .step(0, ORANGE)
+        .step(1_000_000, Light::new(0.3, 0.2, 0.1, 0.6));
+    let mut sensor = AutoTcs34725::new(script, NoInt, Delay);
+    block_on(sensor.init()).unwrap();
+    let lit = block_on(sensor.read()).unwrap();
+
+    advance(1_000_000);
+    let dark = block_on(sensor.read_fixed()).unwrap();
+    assert_eq!(dark.meta.integration_us, lit.meta.integration_us);
+    assert_eq!(dark.meta.gain, lit.meta.gain);
+    assert_eq!(dark.meta.status, ExposureStatus::Underexposed);
+
+    // A normal read re-ranges for the dark scene
+    let ranged = block_on(sensor.read()).unwrap();
+    assert!(ranged.meta.integration_us * ranged.meta.gain as u32 > lit.meta.integration_us * 4);
+}
@@ -0,0 +1,215 @@
+//! Async driver for the color (ALS) engine of the Broadcom APDS-9960.
+//!
+//! The register layout is close to the TCS34725 (ATIME, AGAIN, AVALID and a
+//! CDATAL..BDATAH burst), but registers sit at 0x80+ without a command bit,
+//! cycles are 2.78 ms and the top gain is 64x. Gesture and proximity engines
+//! are left off.
+
+use embassy_time::{Duration, Instant, Timer};
+use embedded_hal_async::i2c::I2c;
+
+use colorpicky_core::sensor::{ColorSensor, Error, ExposureStatus, Metadata, Reading, SensorKind};
+
+pub const ADDRESS: u8 = 0x39;
+
+pub const REG_ENABLE: u8 = 0x80;
+pub const REG_ATIME: u8 = 0x81;
+pub const REG_CONTROL: u8 = 0x8F;
+pub const REG_ID: u8 = 0x92;
+pub const REG_STATUS: u8 = 0x93;
+pub const REG_CDATAL: u8 = 0x94;
+
+pub const MASK_ENABLE_PON: u8 = 0x01; // Power ON
+pub const MASK_ENABLE_AEN: u8 = 0x02; // ALS Enable
+
+pub const MASK_STATUS_AVALID: u8 = 0x01; // ALS cycle complete
+
+pub const ID_APDS9960: u8 = 0xAB;
+/// Reported by some APDS-9960 modules in circulation.
+pub const ID_APDS9960_ALT: u8 = 0xA8;
+
+/// Length of one ALS integration cycle in microseconds.
+pub const CYCLE_US: u32 = 2780;
+
+/// Oscillator warm-up required after setting PON.
+pub const WARMUP_US: u32 = 5700;
+
+/// Default integration: 36 cycles, about 100 ms.
+pub const DEFAULT_CYCLES: u16 = 36;
+
+/// Slack on top of the integration time before giving up on AVALID.
+const READY_MARGIN: Duration = Duration::from_millis(20);
+
+/// INT is not wired, so the app re-reads on this interval.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Gain {
+    X1,
+    #[default]
+    X4,
+    X16,
+    X64,
+}
+
+impl Gain {
+    /// Raw AGAIN field value.
+    pub const fn bits(self) -> u8 {
+        self as u8
+    }
+
+    pub const fn factor(self) -> u8 {
+        match self {
+            Gain::X1 => 1,
+            Gain::X4 => 4,
+            Gain::X16 => 16,
+            Gain::X64 => 64,
+        }
+    }
+}
+
+pub struct Apds9960<I2C> {
+    i2c: I2C,
+    /// 1..=256 integration cycles.
+    cycles: u16,
+    gain: Gain,
+    powered: bool,
+}
+
+impl<I2C, E> Apds9960<I2C>
+where
+    I2C: I2c<Error = E>,
+{
+    pub fn new(i2c: I2C) -> Self {
+        Self {
+            i2c,
+            cycles: DEFAULT_CYCLES,
+            gain: Gain::default(),
+            powered: false,
+        }
+    }
+
+    /// Set the integration time in 2.78 ms cycles (clamped to 1..=256).
+    pub async fn set_integration_cycles(&mut self, cycles: u16) -> Result<(), Error<E>> {
+        let cycles = cycles.clamp(1, 256);
+        self.write_reg(REG_ATIME, (256 - cycles) as u8).await?;
+        self.cycles = cycles;
+        Ok(())
+    }
+
+    pub async fn set_gain(&mut self, gain: Gain) -> Result<(), Error<E>> {
+        self.write_reg(REG_CONTROL, gain.bits()).await?;
+        self.gain = gain;
+        Ok(())
+    }
+
+    pub fn gain(&self) -> Gain {
+        self.gain
+    }
+
+    /// Highest count any channel can reach at the current integration time.
+    pub fn full_scale(&self) -> u16 {
+        (self.cycles as u32 * 1025).min(u16::MAX as u32) as u16
+    }
+
+    async fn power_on(&mut self) -> Result<(), Error<E>> {
+        if !self.powered {
+            self.write_reg(REG_ENABLE, MASK_ENABLE_PON).await?;
+            Timer::after_micros(WARMUP_US as u64).await;
+            self.powered = true;
+        }
+        self.write_reg(REG_ENABLE, MASK_ENABLE_PON | MASK_ENABLE_AEN)
+            .await
+    }
+
+    async fn write_reg(&mut self, reg: u8, value: u8) -> Result<(), Error<E>> {
+        self.i2c
+            .write(ADDRESS, &[reg, value])
+            .await
+            .map_err(Error::Bus)
+    }
+
+    async fn read_reg(&mut self, reg: u8) -> Result<u8, Error<E>> {
+        let mut buf = [0u8; 1];
+        self.i2c
+            .write_read(ADDRESS, &[reg], &mut buf)
+            .await
+            .map_err(Error::Bus)?;
+        Ok(buf[0])
+    }
+}
+
+impl<I2C, E> ColorSensor for Apds9960<I2C>
+where
+    I2C: I2c<Error = E>,
+{
+    type BusError = E;
+
+    async fn init(&mut self) -> Result<(), Error<E>> {
+        match self.read_reg(REG_ID).await? {
+            ID_APDS9960 | ID_APDS9960_ALT => {}
+            other => return Err(Error::UnexpectedId(other)),
+        }
+        self.powered = false;
+        self.set_integration_cycles(self.cycles).await?;
+        self.set_gain(self.gain).await?;
+        self.power_on().await
+    }
+
+    /// Restart the ALS engine and wait for a complete cycle, as the
+    /// TCS34725 async driver does.
+    async fn read(&mut self) -> Result<Reading, Error<E>> {
+        self.write_reg(REG_ENABLE, MASK_ENABLE_PON).await?;
+        self.power_on().await?;
+
+        let integration = Duration::from_micros(self.cycles as u64 * CYCLE_US as u64);
+        let deadline = Instant::now() + integration + READY_MARGIN;
+        Timer::after(integration).await;
+        while self.read_reg(REG_STATUS).await? & MASK_STATUS_AVALID == 0 {
+            if Instant::now() > deadline {
+                return Err(Error::NotReady);
+            }
+            Timer::after_micros(CYCLE_US as u64).await;
+        }
+
+        let mut buf = [0u8; 8];
+        self.i2c
+            .write_read(ADDRESS, &[REG_CDATAL], &mut buf)
+            .await
+            .map_err(Error::Bus)?;
+        let c = u16::from_le_bytes([buf[0], buf[1]]);
+        let full_scale = self.full_scale();
+
+        Ok(Reading {
+            r: u16::from_le_bytes([buf[2], buf[3]]),
+            g: u16::from_le_bytes([buf[4], buf[5]]),
+            b: u16::from_le_bytes([buf[6], buf[7]]),
+            c,
+            meta: Metadata {
+                sensor: SensorKind::Apds9960,
+                integration_us: self.cycles as u32 * CYCLE_US,
+                gain: self.gain.factor(),
+                full_scale,
+                status: ExposureStatus::classify(c, full_scale),
+                ambient: None,
+            },
+            lux: None,
+            cct: None,
+        })
+    }
+
+    async fn sleep(&mut self) -> Result<(), Error<E>> {
+        self.write_reg(REG_ENABLE, 0).await?;
+        self.powered = false;
+        Ok(())
+    }
+
+    async fn wake(&mut self) -> Result<(), Error<E>> {
+        self.power_on().await
+    }
+
+    async fn wait_for_change(&mut self) -> Result<(), Error<E>> {
+        Timer::after(POLL_INTERVAL).await;
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 use embassy_time::{Duration, Instant, Timer};
 use embedded_hal::digital::InputPin;
+use embedded_hal_async::digital::Wait;
 
 pub enum ButtonEvent {
     SingleClick,
@@ -18,7 +19,7 @@ pub struct ButtonInput<P> {
     was_held_long: bool, // Track if we already fired Held events
 }
 
-impl<P: InputPin> ButtonInput<P> {
+impl<P: InputPin + Wait> ButtonInput<P> {
     pub fn new(pin: P) -> Self {
         Self {
             pin,
@@ -35,6 +36,18 @@ impl<P: InputPin> ButtonInput<P> {
         self.is_pressed
     }
 
+    /// Nothing in progress: not pressed and no click waiting to be classified.
+    /// Until then the caller has to keep calling `poll()`.
+    pub fn is_idle(&self) -> bool {
+        !self.is_pressed && self.click_count == 0
+    }
+
+    /// Sleep until the button goes down. On a pin error this returns at once
+    /// and `poll()` reads the level instead.
+    pub async fn wait_for_press(&mut self) {
+        self.pin.wait_for_low().await.ok();
+    }
+
     /// Poll the button state. Should be called frequently.
     /// Returns an event if one occurred.
     pub async fn poll(&mut self) -> Option<ButtonEvent> {
@@ -1,49 +1,265 @@
 #![no_std]
 #![no_main]
 
-mod colors;
+#[cfg(feature = "apds9960")]
+mod apds9960;
 mod input;
 mod ssd1283a;
-mod tcs34725;
+mod storage;
+#[cfg(feature = "veml6040")]
+mod veml6040;
+
+#[cfg(feature = "tcs34725")]
+use colorpicky_core::tcs34725;
+use colorpicky_core::{ccm, describe, harmony, illumination, mixing, sampling};
+
+#[cfg(not(any(feature = "tcs34725", feature = "veml6040", feature = "apds9960")))]
+compile_error!("Enable one sensor feature: tcs34725, veml6040 or apds9960");
+#[cfg(any(
+    all(feature = "tcs34725", feature = "veml6040"),
+    all(feature = "tcs34725", feature = "apds9960"),
+    all(feature = "veml6040", feature = "apds9960"),
+))]
+compile_error!("Only one sensor feature may be enabled; use --no-default-features");
 
 use defmt::{error, info, Debug2Format};
 use defmt_rtt as _;
 use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use embassy_rp::bind_interrupts;
+use embassy_rp::flash::Flash;
 use embassy_rp::gpio::{Input, Level, Output, Pull};
-use embassy_rp::i2c::{Config as I2cConfig, I2c};
+use embassy_rp::i2c::{Config as I2cConfig, I2c, InterruptHandler as I2cInterruptHandler};
+use embassy_rp::peripherals::I2C1;
 use embassy_rp::spi::{Config as SpiConfig, Spi};
 use embassy_time::{Delay, Timer};
 use embedded_graphics::mono_font::{
     ascii::{FONT_6X10, FONT_9X15},
     MonoTextStyle,
 };
-use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::pixelcolor::{Rgb565, Rgb888};
 use embedded_graphics::prelude::*;
 use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
 use embedded_graphics::text::Text;
 use panic_probe as _;
 
-use colors::{match_color, NamedColor};
+use colorpicky_core::adaptation::{Cat, Illuminant};
+use colorpicky_core::calibration::Calibration;
+use colorpicky_core::ccm::{Fit, COLORCHECKER};
+use colorpicky_core::color_space::{decode_srgb8, encode_srgb8, Lab, LinearRgb, Srgb, D50};
+use colorpicky_core::colors::{
+    match_colors, Matches, NamedColor, DEFAULT_MAX_DISTANCE, MAX_DISTANCES,
+};
+use colorpicky_core::contrast::Contrast;
+use colorpicky_core::describe::Description;
+use colorpicky_core::harmony::Scheme;
+use colorpicky_core::lab::DeltaE;
+use colorpicky_core::mixing::Recipe;
+use colorpicky_core::palette::PALETTES;
+use colorpicky_core::sampling::Stats;
+use colorpicky_core::sensor::{ColorSensor, ExposureStatus, Reading};
 use input::{ButtonEvent, ButtonInput};
 use ssd1283a::Ssd1283a;
-use tcs34725::{Rgbc, Tcs34725};
+use storage::Storage;
 
 use embassy_rp::block::ImageDef;
 #[link_section = ".start_block"]
 #[used]
 static IMAGE_DEF: ImageDef = ImageDef::secure_exe();
 
+/// Readings averaged per measurement; fewer while the button is held so
+/// the live preview stays responsive.
+const SAMPLES: usize = 5;
+const SAMPLES_HELD: usize = 3;
+
+/// Palette entries kept per match: the name plus runners-up.
+const MATCHES: usize = 3;
+
+/// LED-on minus LED-off reads; the simulator has no LED, so it reads plain.
+const DIFFERENTIAL: bool = !cfg!(feature = "simulator");
+
+bind_interrupts!(struct Irqs {
+    I2C1_IRQ => I2cInterruptHandler<I2C1>;
+});
+
 #[derive(PartialEq, Clone, Copy)]
 enum AppMode {
     Measuring,
+    Harmony,
+    Mix,
+    LightMeter,
     History,
+    Contrast,
+    Calibrating,
+    Settings,
+}
+
+/// Rows of the Settings screen, top to bottom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Setting {
+    Palette,
+    Names,
+    Formula,
+    MaxDistance,
+    Adaptation,
+    Values,
+}
+
+impl Setting {
+    const ALL: [Setting; 6] = [
+        Setting::Palette,
+        Setting::Names,
+        Setting::Formula,
+        Setting::MaxDistance,
+        Setting::Adaptation,
+        Setting::Values,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Setting::Palette => "Palette",
+            Setting::Names => "Names",
+            Setting::Formula => "Formula",
+            Setting::MaxDistance => "Max dE",
+            Setting::Adaptation => "Adapt",
+            Setting::Values => "Values",
+        }
+    }
+}
+
+/// How the main screen spells out the picked color under its hex code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Readout {
+    #[default]
+    Rgb,
+    Hsv,
+    Hsl,
+    Cmyk,
+    /// CIELAB relative to D50, as printed on paint and ink swatches
+    LabD50,
+}
+
+impl Readout {
+    fn next(self) -> Self {
+        match self {
+            Readout::Rgb => Readout::Hsv,
+            Readout::Hsv => Readout::Hsl,
+            Readout::Hsl => Readout::Cmyk,
+            Readout::Cmyk => Readout::LabD50,
+            Readout::LabD50 => Readout::Rgb,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Readout::Rgb => "RGB",
+            Readout::Hsv => "HSV",
+            Readout::Hsl => "HSL",
+            Readout::Cmyk => "CMYK",
+            Readout::LabD50 => "Lab D50",
+        }
+    }
+
+    /// At most 20 characters, to fit the 130 px line in FONT_6X10.
+    fn write(self, color: Rgb888, buf: &mut heapless::String<32>) {
+        use core::fmt::Write;
+        let srgb = Srgb::from_rgb8(color.r(), color.g(), color.b());
+        let pct = |v: f32| (v * 100.0).round() as u32;
+        let _ = match self {
+            Readout::Rgb => buf.write_fmt(format_args!(
+                "R:{} G:{} B:{}",
+                color.r(),
+                color.g(),
+                color.b()
+            )),
+            Readout::Hsv => {
+                let hsv = srgb.to_hsv();
+                buf.write_fmt(format_args!(
+                    "H:{:.0} S:{}% V:{}%",
+                    hsv.h,
+                    pct(hsv.s),
+                    pct(hsv.v)
+                ))
+            }
+            Readout::Hsl => {
+                let hsl = srgb.to_hsl();
+                buf.write_fmt(format_args!(
+                    "H:{:.0} S:{}% L:{}%",
+                    hsl.h,
+                    pct(hsl.s),
+                    pct(hsl.l)
+                ))
+            }
+            Readout::Cmyk => {
+                let cmyk = srgb.to_cmyk();
+                buf.write_fmt(format_args!(
+                    "C{} M{} Y{} K{}",
+                    pct(cmyk.c),
+                    pct(cmyk.m),
+                    pct(cmyk.y),
+                    pct(cmyk.k)
+                ))
+            }
+            Readout::LabD50 => {
+                let lab = srgb.to_xyz().d65_to_d50().to_lab(D50);
+                buf.write_fmt(format_args!("D50 {:.0} {:.0} {:.0}", lab.l, lab.a, lab.b))
+            }
+        };
+    }
 }
 
 struct AppState {
     mode: AppMode,
     history: [Option<NamedColor>; 10],
     current_reading: Option<NamedColor>,
-    current_rgbc: Rgbc,
+    last_reading: Reading,
+    /// Spread of the burst behind `last_reading`
+    last_stats: Stats,
+    /// Color difference formula used for matching
+    formula: DeltaE,
+    /// Nearest names to `current_reading`, with distances
+    matches: Matches<MATCHES>,
+    /// Index into `MAX_DISTANCES`: beyond this CIEDE2000 distance the color
+    /// is described instead of named
+    max_distance: usize,
+    /// Index into `PALETTES` of the names being matched against
+    palette: usize,
+    /// Name colors by lightness, chroma and hue instead of the palette
+    describe: bool,
+    /// Settings screen: row under the cursor, index into `Setting::ALL`
+    setting: usize,
+    /// Main screen: how the color is spelled out under the hex code
+    readout: Readout,
+    /// Room light uncalibrated readings are adapted from, without `DIFFERENTIAL`
+    illuminant: Illuminant,
+    /// The LED's own white, read off a white tile; with `DIFFERENTIAL` the
+    /// LED is the only light in the reading, so this replaces `illuminant`
+    led_white: Illuminant,
+    /// Transform used to adapt readings to D65
+    cat: Cat,
+    /// Harmony shown on the Harmony screen
+    scheme: Scheme,
+    /// Mix screen: saved colors used as base paints, and the best recipe
+    /// from them for `current_reading`
+    mix_paints: heapless::Vec<NamedColor, 10>,
+    mix_recipe: Option<Recipe>,
+    /// Contrast screen: history slot under the cursor
+    contrast_cursor: usize,
+    /// Contrast screen: picked text and background colors
+    contrast_fg: Option<NamedColor>,
+    contrast_bg: Option<NamedColor>,
+    /// Black/white tile references; without one colors are clear-normalized
+    calibration: Option<Calibration>,
+    /// Black tile reading while waiting for the white one
+    cal_black: Option<Reading>,
+    /// Chart patches collected so far, after the tiles are done
+    chart: Option<Fit>,
+    /// Correction fitted from the chart, applied on top of `calibration`
+    correction: Option<ccm::Matrix>,
+    /// Outcome of the last calibration step, shown on the calibration screen
+    cal_message: Option<&'static str>,
+    /// Set while the sensor is failing; replaces the measuring screens
+    sensor_fault: Option<&'static str>,
 }
 
 impl AppState {
@@ -52,7 +268,33 @@ impl AppState {
             mode: AppMode::Measuring,
             history: [None; 10], // Default value
             current_reading: None,
-            current_rgbc: Rgbc::default(),
+            last_reading: Reading::default(),
+            last_stats: Stats::default(),
+            formula: DeltaE::default(),
+            matches: Matches {
+                entries: heapless::Vec::new(),
+                close: 0,
+            },
+            max_distance: DEFAULT_MAX_DISTANCE,
+            palette: 0,
+            describe: false,
+            setting: 0,
+            readout: Readout::default(),
+            illuminant: Illuminant::default(),
+            led_white: Illuminant::default(),
+            cat: Cat::default(),
+            scheme: Scheme::default(),
+            mix_paints: heapless::Vec::new(),
+            mix_recipe: None,
+            contrast_cursor: 0,
+            contrast_fg: None,
+            contrast_bg: None,
+            calibration: None,
+            cal_black: None,
+            chart: None,
+            correction: None,
+            cal_message: None,
+            sensor_fault: None,
         }
     }
 
@@ -78,6 +320,7 @@ impl AppState {
             let new_c = NamedColor {
                 name: color.name,
                 color: color.color,
+                description: color.description,
             };
             self.history[0] = Some(new_c);
 
@@ -87,28 +330,238 @@ impl AppState {
 
     fn toggle_mode(&mut self) {
         self.mode = match self.mode {
-            AppMode::Measuring => AppMode::History,
-            AppMode::History => AppMode::Measuring,
+            AppMode::Measuring => AppMode::Harmony,
+            AppMode::Harmony => AppMode::Mix,
+            AppMode::Mix => AppMode::LightMeter,
+            AppMode::LightMeter => AppMode::History,
+            AppMode::History => AppMode::Contrast,
+            AppMode::Contrast => AppMode::Calibrating,
+            AppMode::Calibrating => AppMode::Settings,
+            AppMode::Settings => AppMode::Measuring,
         };
+        self.cal_black = None;
+        self.chart = None;
+        self.cal_message = None;
+        self.contrast_cursor = 0;
+        self.contrast_fg = None;
+        self.contrast_bg = None;
+        self.setting = 0;
+        if self.mode == AppMode::Mix {
+            self.plan_mix();
+        }
         info!("Mode switched");
     }
 
+    /// Work out the Mix screen's recipe. The target and history only change
+    /// on other screens, so this runs once on entering Mix rather than on
+    /// every redraw.
+    fn plan_mix(&mut self) {
+        self.mix_paints.clear();
+        self.mix_recipe = None;
+        let Some(target) = &self.current_reading else {
+            return;
+        };
+        // Saved colors are the base paints; skip the target if it was saved too
+        for c in self.history.iter().flatten() {
+            if c.color != target.color {
+                let _ = self.mix_paints.push(*c);
+            }
+        }
+        let colors: heapless::Vec<Rgb888, 10> = self.mix_paints.iter().map(|p| p.color).collect();
+        self.mix_recipe = mixing::recipe(target.color, &colors, self.formula);
+    }
+
+    /// Take a calibration reading: black tile, white tile, then the
+    /// ColorChecker patches in chart order.
+    fn capture_reference(&mut self, reading: Reading) {
+        if let (Some(cal), Some(fit)) = (&self.calibration, &mut self.chart) {
+            let measured = cal.reflectance(&reading);
+            let (name, reference) = COLORCHECKER[fit.len()];
+            // Logged in the format core/examples/fit_ccm.rs reads back
+            info!(
+                "Patch {}: {} {} {}",
+                fit.len() + 1,
+                measured[0],
+                measured[1],
+                measured[2]
+            );
+            fit.add(measured, reference.map(decode_srgb8));
+            self.cal_message = Some(name);
+
+            if fit.len() == COLORCHECKER.len() {
+                self.correction = fit.solve();
+                self.cal_message = Some(match self.correction {
+                    Some(_) => "Chart fitted",
+                    None => "Chart fit failed",
+                });
+                self.chart = None;
+            }
+            return;
+        }
+
+        match self.cal_black.take() {
+            None => {
+                self.cal_black = Some(reading);
+                self.cal_message = None;
+            }
+            Some(black) => match Calibration::new(&black, &reading) {
+                Ok(cal) => {
+                    info!("Calibration measured");
+                    self.calibration = Some(cal);
+                    // An old correction was fitted against the old tiles
+                    self.correction = None;
+                    self.chart = Some(Fit::new(true));
+                    self.cal_message = Some("Calibrated");
+                }
+                // Start over from the black tile
+                Err(e) => self.cal_message = Some(e.label()),
+            },
+        }
+    }
+
+    /// Color to display and match: tile-calibrated and chart-corrected when
+    /// available, otherwise clear-normalized and adapted to D65.
+    fn display_rgb(&self, reading: &Reading) -> Option<(u8, u8, u8)> {
+        let Some(cal) = &self.calibration else {
+            let linear = self
+                .adapting_from()
+                .to_d65(clear_normalized(reading)?, self.cat);
+            return Some(linear.to_srgb().to_rgb8());
+        };
+        // No adaptation here: the white tile was read under the same light,
+        // so reflectance is already balanced to it
+        let linear = cal.reflectance(reading);
+        let [r, g, b] = match &self.correction {
+            Some(m) => m.apply(linear),
+            None => linear,
+        }
+        .map(encode_srgb8);
+        Some((r, g, b))
+    }
+
+    /// Light uncalibrated readings are taken under: the LED in differential
+    /// mode, since the room light is subtracted out, otherwise the room's.
+    fn adapting_from(&self) -> &Illuminant {
+        if DIFFERENTIAL {
+            &self.led_white
+        } else {
+            &self.illuminant
+        }
+    }
+
+    /// Use the light the sensor sees now (light meter reading) as the
+    /// room illuminant to adapt from.
+    fn measure_illuminant(&mut self) {
+        if let Some(illuminant) =
+            clear_normalized(&self.last_reading).and_then(Illuminant::measured)
+        {
+            self.illuminant = illuminant;
+            info!("Measured white: {:?}", Debug2Format(&illuminant.white()));
+        }
+    }
+
+    /// Use a differential reading of a white tile as the LED's white.
+    fn measure_led_white(&mut self, reading: &Reading) {
+        if let Some(white) = clear_normalized(reading).and_then(Illuminant::measured) {
+            self.led_white = white;
+            // Force a fresh match under the new white
+            self.current_reading = None;
+            info!("LED white: {:?}", Debug2Format(&white.white()));
+        }
+    }
+
+    fn clear_calibration(&mut self) {
+        self.calibration = None;
+        self.correction = None;
+        self.chart = None;
+        self.cal_black = None;
+        self.cal_message = Some("Calibration cleared");
+        info!("Calibration cleared");
+    }
+
+    /// Settings screen hold: step the value of the row under the cursor.
+    fn change_setting(&mut self) {
+        match Setting::ALL[self.setting] {
+            Setting::Palette => {
+                self.palette = (self.palette + 1) % PALETTES.len();
+                info!("Palette: {}", PALETTES[self.palette].name);
+            }
+            Setting::Names => self.describe = !self.describe,
+            Setting::Formula => {
+                self.formula = self.formula.next();
+                info!("Formula: {}", self.formula.label());
+            }
+            Setting::MaxDistance => {
+                self.max_distance = (self.max_distance + 1) % MAX_DISTANCES.len();
+                info!("Max dE00: {}", MAX_DISTANCES[self.max_distance]);
+            }
+            Setting::Adaptation => {
+                self.cat = self.cat.next();
+                info!("Adaptation: {}", self.cat.label());
+            }
+            Setting::Values => {
+                // Display only, no need to match again
+                self.readout = self.readout.next();
+                return;
+            }
+        }
+        // Force a fresh match with the new settings on the next read
+        self.current_reading = None;
+    }
+
+    /// Contrast screen tap: move to the next saved color, or start over
+    /// once both colors are picked.
+    fn contrast_next(&mut self) {
+        if self.contrast_fg.is_some() && self.contrast_bg.is_some() {
+            self.contrast_fg = None;
+            self.contrast_bg = None;
+            self.contrast_cursor = 0;
+            return;
+        }
+        let len = self.history.len();
+        if let Some(step) =
+            (1..=len).find(|step| self.history[(self.contrast_cursor + step) % len].is_some())
+        {
+            self.contrast_cursor = (self.contrast_cursor + step) % len;
+        }
+    }
+
+    /// Contrast screen hold: take the color under the cursor as the text
+    /// color, then as the background.
+    fn contrast_pick(&mut self) {
+        let Some(color) = self.history[self.contrast_cursor] else {
+            return;
+        };
+        if self.contrast_fg.is_none() {
+            self.contrast_fg = Some(color);
+        } else if self.contrast_bg.is_none() {
+            self.contrast_bg = Some(color);
+            if let (Some(fg), Some(bg)) = (&self.contrast_fg, &self.contrast_bg) {
+                let c = Contrast::between(fg, bg);
+                info!(
+                    "Contrast {} on {}: {}:1, APCA Lc {}",
+                    fg.name, bg.name, c.ratio, c.apca
+                );
+            }
+        }
+    }
+
     fn clear_history(&mut self) {
         self.history = [None; 10];
         info!("History cleared");
     }
 }
 
-// Implement Clone/Copy for NamedColor manually since we didn't derive it
-impl Clone for NamedColor {
-    fn clone(&self) -> Self {
-        NamedColor {
-            name: self.name,
-            color: self.color,
-        }
-    }
+/// Clear-normalized channels as linear sRGB, read the same way as the
+/// uncalibrated display color.
+fn clear_normalized(reading: &Reading) -> Option<LinearRgb> {
+    let (r, g, b) = reading.rgb8()?;
+    Some(LinearRgb::new(
+        decode_srgb8(r),
+        decode_srgb8(g),
+        decode_srgb8(b),
+    ))
 }
-impl Copy for NamedColor {}
 
 #[embassy_executor::main]
 async fn main(_spawner: Spawner) {
@@ -144,16 +597,50 @@ async fn main(_spawner: Spawner) {
     let mut i2c_config = I2cConfig::default();
     i2c_config.frequency = 400_000;
 
-    let i2c = I2c::new_blocking(p.I2C1, scl, sda, i2c_config);
-    let mut sensor = Tcs34725::new(i2c);
+    let i2c = I2c::new_async(p.I2C1, scl, sda, Irqs, i2c_config);
 
-    if let Err(e) = sensor.init() {
-        error!("Sensor init failed: {:?}", Debug2Format(&e));
-    } else {
-        info!("Sensor initialized");
-    }
-    // Enable PON/AEN
-    let _ = sensor.enable();
+    // Simulated TCS34725 instead of the real bus, cycling through a few colors
+    #[cfg(feature = "simulator")]
+    let i2c = {
+        use tcs34725::sim::{Light, Tcs34725Sim};
+        let _ = i2c;
+        Tcs34725Sim::new()
+            .with_clock(|| embassy_time::Instant::now().as_micros())
+            .step(0, Light::new(60.0, 15.0, 12.0, 85.0))
+            .step(3_000_000, Light::new(14.0, 45.0, 20.0, 78.0))
+            .step(6_000_000, Light::new(10.0, 22.0, 55.0, 86.0))
+            .step(9_000_000, Light::new(70.0, 68.0, 60.0, 195.0))
+            .step(12_000_000, Light::new(6.0, 4.0, 3.0, 13.0))
+            .repeat(15_000_000)
+    };
+
+    // INT (GP8, open drain, active low) asserts when the clear channel leaves
+    // the window armed around the last reading; the main loop sleeps until then
+    #[cfg(all(feature = "tcs34725", not(feature = "simulator")))]
+    let mut sensor =
+        tcs34725::color_sensor::AutoTcs34725::new(i2c, Input::new(p.PIN_8, Pull::Up), Delay);
+    #[cfg(feature = "simulator")]
+    let mut sensor = tcs34725::color_sensor::AutoTcs34725::new(i2c, tcs34725::sim::NoInt, Delay);
+    #[cfg(feature = "veml6040")]
+    let mut sensor = veml6040::Veml6040::new(i2c);
+    #[cfg(feature = "apds9960")]
+    let mut sensor = apds9960::Apds9960::new(i2c);
+
+    // Breakout LED enable (GP9, high = on), driven for LED-on minus LED-off
+    // reads so room light cancels out
+    let mut sensor = illumination::Illuminated::new(sensor, Output::new(p.PIN_9, Level::Low));
+    sensor.set_differential(DIFFERENTIAL);
+
+    let sensor_fault = match sensor.init().await {
+        Ok(()) => {
+            info!("Sensor initialized");
+            None
+        }
+        Err(e) => {
+            error!("Sensor init failed: {:?}", Debug2Format(&e));
+            Some(e.label())
+        }
+    };
 
     // ==================
     // Button Setup
@@ -164,9 +651,22 @@ async fn main(_spawner: Spawner) {
     // ==================
     // State & Loop
     // ==================
+    let mut storage = Storage::new(Flash::new_blocking(p.FLASH));
     let mut state = AppState::new();
+    state.sensor_fault = sensor_fault;
+    match storage.load_calibration() {
+        Ok(cal) => {
+            info!("Calibration loaded from flash");
+            state.calibration = Some(cal);
+        }
+        Err(e) => info!("No saved calibration: {}", e.label()),
+    }
     let mut needs_redraw = true;
-    let mut prev_color_name: Option<&'static str> = None; // Track changes to avoid flicker
+    let mut prev_color_name: Option<(&'static str, Option<Description>)> = None; // Track changes to avoid flicker
+    let mut prev_status = state.last_reading.status_label();
+    let mut prev_fault = state.sensor_fault;
+    // Set when INT reports a new scene; read once, then wait for the next
+    let mut scene_changed = true;
 
     // Font styles
     let style_title = MonoTextStyle::new(&FONT_9X15, Rgb565::YELLOW); // Larger title font
@@ -184,9 +684,59 @@ async fn main(_spawner: Spawner) {
                     if state.mode == AppMode::Measuring {
                         state.push_history();
                     }
+                    // Calibration: first tap captures the black tile, second the white
+                    if state.mode == AppMode::Calibrating {
+                        let before = state.calibration;
+                        match sampling::sample(&mut sensor, SAMPLES).await {
+                            Ok(filtered) => state.capture_reference(filtered.reading),
+                            Err(e) => state.cal_message = Some(e.label()),
+                        }
+                        if state.calibration != before {
+                            store_calibration(&mut storage, &mut state);
+                        }
+                        needs_redraw = true;
+                    }
+                    if state.mode == AppMode::Settings {
+                        state.setting = (state.setting + 1) % Setting::ALL.len();
+                        needs_redraw = true;
+                    }
+                    if state.mode == AppMode::Harmony {
+                        state.scheme = state.scheme.next();
+                        needs_redraw = true;
+                    }
+                    if state.mode == AppMode::Contrast {
+                        state.contrast_next();
+                        needs_redraw = true;
+                    }
+                    // Room illuminants only apply without the LED
+                    if state.mode == AppMode::LightMeter && !DIFFERENTIAL {
+                        state.illuminant = state.illuminant.next();
+                        needs_redraw = true;
+                    }
                 }
                 ButtonEvent::DoubleClick => {
                     state.toggle_mode();
+                    // Measuring screens start from a fresh reading
+                    scene_changed = true;
+                    // The light meter wants the room light, not the LED
+                    sensor.set_differential(DIFFERENTIAL && state.mode != AppMode::LightMeter);
+                    // Nothing is measured in History mode, so power the sensor down
+                    // (it stays down through Contrast, which follows)
+                    let power = match state.mode {
+                        AppMode::History => sensor.sleep().await,
+                        AppMode::Calibrating => sensor.wake().await,
+                        AppMode::Measuring
+                        | AppMode::Harmony
+                        | AppMode::Mix
+                        | AppMode::LightMeter
+                        | AppMode::Contrast
+                        | AppMode::Settings => Ok(()),
+                    };
+                    // Reported like a failed read; the next read redoes setup
+                    if let Err(e) = power {
+                        error!("Sensor power change failed: {:?}", Debug2Format(&e));
+                        state.sensor_fault = Some(e.label());
+                    }
                     needs_redraw = true;
                 }
                 ButtonEvent::LongPress => {
@@ -211,69 +761,239 @@ async fn main(_spawner: Spawner) {
                         // Long hold + release in History = clear history
                         state.clear_history();
                         needs_redraw = true;
+                    } else if state.mode == AppMode::Calibrating {
+                        // Holds end in Released (never LongPress), like History's clear
+                        state.clear_calibration();
+                        store_calibration(&mut storage, &mut state);
+                        needs_redraw = true;
+                    } else if state.mode == AppMode::Contrast {
+                        state.contrast_pick();
+                        needs_redraw = true;
+                    } else if state.mode == AppMode::LightMeter && DIFFERENTIAL {
+                        // The light meter reads the room; the LED white needs
+                        // one LED-on minus LED-off read of the white tile
+                        sensor.set_differential(true);
+                        let result = sampling::sample(&mut sensor, SAMPLES).await;
+                        sensor.set_differential(false);
+                        match result {
+                            Ok(filtered) => state.measure_led_white(&filtered.reading),
+                            Err(e) => error!("LED white read failed: {:?}", Debug2Format(&e)),
+                        }
+                        needs_redraw = true;
+                    } else if state.mode == AppMode::LightMeter {
+                        state.measure_illuminant();
+                        needs_redraw = true;
+                    } else if state.mode == AppMode::Settings {
+                        state.change_setting();
+                        needs_redraw = true;
                     }
                 }
             }
         }
 
-        // 2. Sensor Read (Only in Measuring mode)
-        if state.mode == AppMode::Measuring {
-            match sensor.read_all() {
-                Ok(rgbc) => {
-                    state.current_rgbc = rgbc;
-                    if rgbc.c > 0 {
-                        let r8 = (rgbc.r as u32 * 255 / rgbc.c as u32) as u8;
-                        let g8 = (rgbc.g as u32 * 255 / rgbc.c as u32) as u8;
-                        let b8 = (rgbc.b as u32 * 255 / rgbc.c as u32) as u8;
+        // 2. Sensor Read (Not in History/Settings mode, and only when something changed;
+        // Harmony and Mix work on the color picked before switching to them)
+        let sensor_changed =
+            scene_changed || state.current_reading.is_none() || state.sensor_fault.is_some();
+        let mut sensor_read = false;
+        let measuring = !matches!(
+            state.mode,
+            AppMode::History
+                | AppMode::Contrast
+                | AppMode::Settings
+                | AppMode::Harmony
+                | AppMode::Mix
+        );
+        if measuring && (is_sampling || sensor_changed) {
+            let samples = if is_sampling { SAMPLES_HELD } else { SAMPLES };
+            let result = match state.sensor_fault {
+                // Redo setup first in case the sensor was reconnected
+                Some(_) => match sensor.init().await {
+                    Ok(()) => sampling::sample(&mut sensor, samples).await,
+                    Err(e) => Err(e),
+                },
+                None => sampling::sample(&mut sensor, samples).await,
+            };
+            scene_changed = false;
+
+            match result {
+                Ok(filtered) => {
+                    let reading = filtered.reading;
+                    sensor_read = true;
+                    state.sensor_fault = None;
+                    state.last_reading = reading;
+                    state.last_stats = filtered.stats;
 
-                        let name = match_color(r8, g8, b8);
+                    // Calibrated reflectance keeps lightness; the fallback only keeps hue
+                    if let Some((r8, g8, b8)) = state.display_rgb(&reading) {
+                        state.matches = match_colors(
+                            PALETTES[state.palette],
+                            r8,
+                            g8,
+                            b8,
+                            state.formula,
+                            MAX_DISTANCES[state.max_distance],
+                        );
+                        // Colors with no close match are described rather than
+                        // left unnamed
+                        let best = state.matches.best();
+                        let description = (state.describe || best.is_none())
+                            .then(|| describe::describe(&Lab::from_srgb8(r8, g8, b8)));
                         let matched_color = NamedColor {
-                            name,
-                            color: embedded_graphics::pixelcolor::Rgb888::new(r8, g8, b8),
+                            name: best.map_or("No close match", |m| m.name),
+                            color: Rgb888::new(r8, g8, b8),
+                            description,
                         };
                         state.current_reading = Some(matched_color);
                     }
                 }
-                Err(_) => {}
+                Err(e) => {
+                    if state.sensor_fault.is_none() {
+                        error!("Sensor read failed: {:?}", Debug2Format(&e));
+                    }
+                    // Don't leave the last good color on screen as if it were live
+                    state.sensor_fault = Some(e.label());
+                    state.current_reading = None;
+                }
             }
         }
 
         // 3. Draw UI
         // If sampling (button held), always redraw for real-time feedback
         // Otherwise, only redraw on changes
-        let current_name = state.current_reading.as_ref().map(|c| c.name);
-        let color_changed = prev_color_name != current_name;
+        let current_name = state
+            .current_reading
+            .as_ref()
+            .map(|c| (c.name, c.description));
+        let color_changed =
+            prev_color_name != current_name || prev_status != state.last_reading.status_label();
 
-        if needs_redraw || is_sampling || (state.mode == AppMode::Measuring && color_changed) {
+        if needs_redraw
+            || prev_fault != state.sensor_fault
+            || is_sampling
+            || (state.mode == AppMode::Measuring && color_changed)
+            || (state.mode == AppMode::LightMeter && sensor_read)
+        {
             display.fill_screen(Rgb565::BLACK).unwrap();
             needs_redraw = false;
             prev_color_name = current_name;
+            prev_status = state.last_reading.status_label();
+            prev_fault = state.sensor_fault;
 
-            match state.mode {
-                AppMode::Measuring => {
+            match (state.mode, state.sensor_fault) {
+                (AppMode::Measuring | AppMode::LightMeter | AppMode::Calibrating, Some(fault)) => {
+                    draw_fault_screen(&mut display, fault, style_text, style_small);
+                }
+                (AppMode::Measuring, None) => {
                     draw_main_screen(&mut display, &state, style_title, style_text, style_small);
                 }
-                AppMode::History => {
+                (AppMode::LightMeter, None) => {
+                    draw_light_screen(&mut display, &state, style_title, style_text, style_small);
+                }
+                (AppMode::Calibrating, None) => {
+                    draw_calibration_screen(
+                        &mut display,
+                        &state,
+                        style_title,
+                        style_text,
+                        style_small,
+                    );
+                }
+                (AppMode::Harmony, _) => {
+                    draw_harmony_screen(&mut display, &state, style_title, style_text, style_small);
+                }
+                (AppMode::Mix, _) => {
+                    draw_mix_screen(&mut display, &state, style_title, style_text, style_small);
+                }
+                (AppMode::History, _) => {
                     draw_history_screen(&mut display, &state, style_title, style_text);
                 }
+                (AppMode::Contrast, _) => {
+                    draw_contrast_screen(
+                        &mut display,
+                        &state,
+                        style_title,
+                        style_text,
+                        style_small,
+                    );
+                }
+                (AppMode::Settings, _) => {
+                    draw_settings_screen(
+                        &mut display,
+                        &state,
+                        style_title,
+                        style_text,
+                        style_small,
+                    );
+                }
             }
         }
 
-        // Delay - shorter when sampling for responsiveness
+        // Sleep until something happens: a press, or (while measuring) INT
+        // reporting a new scene. Otherwise poll() paces the loop while a
+        // click is being classified.
         if is_sampling {
             Timer::after_millis(30).await;
-        } else {
-            Timer::after_millis(100).await;
+        } else if button.is_idle() {
+            if !measuring {
+                button.wait_for_press().await;
+            } else if state.sensor_fault.is_some() {
+                // Keep retrying init so a reconnected sensor comes back
+                select(button.wait_for_press(), Timer::after_millis(100)).await;
+            } else {
+                match select(button.wait_for_press(), sensor.wait_for_change()).await {
+                    Either::First(()) => {}
+                    Either::Second(Ok(())) => scene_changed = true,
+                    Either::Second(Err(e)) => {
+                        error!("Sensor wait failed: {:?}", Debug2Format(&e));
+                        state.sensor_fault = Some(e.label());
+                        state.current_reading = None;
+                    }
+                }
+            }
         }
     }
 }
 
+/// Save the current calibration (or its absence) so it survives a restart.
+fn store_calibration(storage: &mut Storage<'_>, state: &mut AppState) {
+    match storage.save_calibration(state.calibration.as_ref()) {
+        Ok(()) => info!("Calibration saved to flash"),
+        Err(e) => {
+            error!("Saving calibration failed: {:?}", Debug2Format(&e));
+            state.cal_message = Some("Not saved to flash");
+        }
+    }
+}
+
+fn draw_fault_screen<D>(
+    display: &mut D,
+    fault: &str,
+    style_text: MonoTextStyle<Rgb565>,
+    style_small: MonoTextStyle<Rgb565>,
+) where
+    D: DrawTarget<Color = Rgb565>,
+{
+    // "Sensor Fault" = 12 chars * 9px = 108px. Center on 130: (130-108)/2 = 11
+    let style_fault = MonoTextStyle::new(&FONT_9X15, Rgb565::RED);
+    Text::new("Sensor Fault", Point::new(11, 12), style_fault)
+        .draw(display)
+        .ok();
+
+    Text::new(fault, Point::new(5, 60), style_text)
+        .draw(display)
+        .ok();
+    Text::new("Retrying...", Point::new(5, 124), style_small)
+        .draw(display)
+        .ok();
+}
+
 fn draw_main_screen<D>(
     display: &mut D,
     state: &AppState,
     style_title: MonoTextStyle<Rgb565>,
     style_text: MonoTextStyle<Rgb565>,
-    _style_small: MonoTextStyle<Rgb565>,
+    style_small: MonoTextStyle<Rgb565>,
 ) where
     D: DrawTarget<Color = Rgb565>,
 {
@@ -283,21 +1003,49 @@ fn draw_main_screen<D>(
         .ok();
 
     if let Some(c) = &state.current_reading {
-        // Color Box: 115x55 rectangle, centered (130-115)/2 = 7.5 ≈ 7
+        // Color Box: 115x45 rectangle, centered (130-115)/2 = 7.5 ≈ 7
         let box_color = Rgb565::from(c.color);
-        Rectangle::new(Point::new(7, 20), Size::new(115, 55))
+        Rectangle::new(Point::new(7, 20), Size::new(115, 45))
             .into_styled(PrimitiveStyle::with_fill(box_color))
             .draw(display)
             .ok();
 
-        // Color Name (y=88)
-        Text::new(c.name, Point::new(5, 88), style_text)
+        use core::fmt::Write;
+        let mut buf = heapless::String::<32>::new();
+
+        // Runner-up name (y=76), when it is also a close match. When
+        // describing, the palette name moves here instead.
+        let best = state.matches.best();
+        let near = if state.describe { best } else { None };
+        if let Some(m) = near {
+            let _ = buf.write_fmt(format_args!("Near: {}", m.name));
+        } else if let Some(m) = state.matches.also_close().first() {
+            let _ = buf.write_fmt(format_args!("Also: {}", m.name));
+        }
+        Text::new(&buf, Point::new(5, 76), style_small)
+            .draw(display)
+            .ok();
+
+        // Color Name (y=88), after the catalogue code if the palette has one
+        buf.clear();
+        if let Some(description) = &c.description {
+            let _ = buf.write_fmt(format_args!("{}", description));
+        } else {
+            match best.and_then(|m| m.code) {
+                Some(code) => {
+                    let _ = buf.write_fmt(format_args!("{} {}", code, c.name));
+                }
+                None => {
+                    let _ = buf.push_str(c.name);
+                }
+            }
+        }
+        Text::new(&buf, Point::new(5, 88), style_text)
             .draw(display)
             .ok();
 
         // Hex code (y=100)
-        use core::fmt::Write;
-        let mut buf = heapless::String::<32>::new();
+        buf.clear();
         let _ = buf.write_fmt(format_args!(
             "#{0:02X}{1:02X}{2:02X}",
             c.color.r(),
@@ -308,17 +1056,44 @@ fn draw_main_screen<D>(
             .draw(display)
             .ok();
 
-        // RGB code (y=112)
+        // Distance to the nearest name, right-aligned next to the hex code
+        if let Some(nearest) = state.matches.entries.first() {
+            buf.clear();
+            let _ = buf.write_fmt(format_args!(
+                "{} {:.1}",
+                state.formula.label(),
+                nearest.distance
+            ));
+            let x = 125 - buf.len() as i32 * 6;
+            Text::new(&buf, Point::new(x, 100), style_small)
+                .draw(display)
+                .ok();
+        }
+
+        // RGB, HSV, HSL, CMYK or D50 Lab values (y=112)
         buf.clear();
-        let _ = buf.write_fmt(format_args!(
-            "R:{} G:{} B:{}",
-            c.color.r(),
-            c.color.g(),
-            c.color.b()
-        ));
+        state.readout.write(c.color, &mut buf);
         Text::new(&buf, Point::new(5, 112), style_text)
             .draw(display)
             .ok();
+
+        // Exposure / ambient status (y=124), flagged in red when the reading can't be trusted
+        let reading = &state.last_reading;
+        let status_style = match reading.is_valid() {
+            true => style_small,
+            false => MonoTextStyle::new(&FONT_6X10, Rgb565::RED),
+        };
+        Text::new(reading.status_label(), Point::new(5, 124), status_style)
+            .draw(display)
+            .ok();
+
+        // Repeatability of the burst, right-aligned on the status line
+        buf.clear();
+        let _ = buf.write_fmt(format_args!("+/-{:.1}%", state.last_stats.spread_percent()));
+        let x = 125 - buf.len() as i32 * 6;
+        Text::new(&buf, Point::new(x, 124), style_small)
+            .draw(display)
+            .ok();
     } else {
         Text::new("Place on color...", Point::new(10, 60), style_text)
             .draw(display)
@@ -326,6 +1101,89 @@ fn draw_main_screen<D>(
     }
 }
 
+fn draw_light_screen<D>(
+    display: &mut D,
+    state: &AppState,
+    style_title: MonoTextStyle<Rgb565>,
+    style_text: MonoTextStyle<Rgb565>,
+    style_small: MonoTextStyle<Rgb565>,
+) where
+    D: DrawTarget<Color = Rgb565>,
+{
+    // "Light Meter" = 11 chars * 9px = 99px. Center on 130: (130-99)/2 = 15
+    Text::new("Light Meter", Point::new(15, 12), style_title)
+        .draw(display)
+        .ok();
+
+    use core::fmt::Write;
+    let reading = &state.last_reading;
+    let mut buf = heapless::String::<32>::new();
+
+    // Illuminance (y=45), in the title font so it reads at a glance
+    match reading.lux {
+        Some(lux) => {
+            let _ = buf.write_fmt(format_args!("{:.0} lx", lux));
+        }
+        None => {
+            let _ = buf.write_str("-- lx");
+        }
+    }
+    Text::new(&buf, Point::new(5, 45), style_title)
+        .draw(display)
+        .ok();
+
+    // Color temperature (y=70)
+    buf.clear();
+    match reading.cct {
+        Some(cct) => {
+            let _ = buf.write_fmt(format_args!("CCT: {:.0} K", cct));
+        }
+        None => {
+            let _ = buf.write_str("CCT: --");
+        }
+    }
+    Text::new(&buf, Point::new(5, 70), style_text)
+        .draw(display)
+        .ok();
+
+    // Light that uncalibrated readings are adapted from (y=86)
+    buf.clear();
+    let hint = if DIFFERENTIAL {
+        let _ = buf.write_fmt(format_args!("LED: {}", state.led_white.label()));
+        "Hold: LED white tile"
+    } else {
+        let _ = buf.write_fmt(format_args!("Light: {}", state.illuminant.label()));
+        "Tap: next Hold: this"
+    };
+    Text::new(&buf, Point::new(5, 86), style_text)
+        .draw(display)
+        .ok();
+    Text::new(hint, Point::new(5, 98), style_small)
+        .draw(display)
+        .ok();
+
+    // Settings the reading was taken with (y=112)
+    buf.clear();
+    let _ = buf.write_fmt(format_args!(
+        "{:.1}ms x{}",
+        reading.meta.integration_us as f32 / 1000.0,
+        reading.meta.gain
+    ));
+    Text::new(&buf, Point::new(5, 112), style_small)
+        .draw(display)
+        .ok();
+
+    // Exposure status (y=124)
+    let status = reading.meta.status;
+    let status_style = match status {
+        ExposureStatus::Valid => style_small,
+        _ => MonoTextStyle::new(&FONT_6X10, Rgb565::RED),
+    };
+    Text::new(status.label(), Point::new(5, 124), status_style)
+        .draw(display)
+        .ok();
+}
+
 fn draw_history_screen<D>(
     display: &mut D,
     state: &AppState,
@@ -344,7 +1202,7 @@ fn draw_history_screen<D>(
             // 1 #HEX Name
             use core::fmt::Write;
             let mut buf = heapless::String::<32>::new();
-            let _ = buf.write_fmt(format_args!("{}. {} ", i + 1, c.name));
+            let _ = buf.write_fmt(format_args!("{}. {} ", i + 1, c));
             // Hex is too long for line? "1. #FFFFFF Name"
 
             Text::new(&buf, Point::new(5, y), style_text)
@@ -364,3 +1222,367 @@ fn draw_history_screen<D>(
         }
     }
 }
+
+fn draw_calibration_screen<D>(
+    display: &mut D,
+    state: &AppState,
+    style_title: MonoTextStyle<Rgb565>,
+    style_text: MonoTextStyle<Rgb565>,
+    style_small: MonoTextStyle<Rgb565>,
+) where
+    D: DrawTarget<Color = Rgb565>,
+{
+    // "Calibrate" = 9 chars * 9px = 81px. Center on 130: (130-81)/2 = 24
+    Text::new("Calibrate", Point::new(24, 12), style_title)
+        .draw(display)
+        .ok();
+
+    if let Some(fit) = &state.chart {
+        // Chart step: name the next patch and show its reference color
+        let (name, [r, g, b]) = COLORCHECKER[fit.len()];
+        use core::fmt::Write;
+        let mut buf = heapless::String::<32>::new();
+        let _ = buf.write_fmt(format_args!(
+            "Patch {}/{}",
+            fit.len() + 1,
+            COLORCHECKER.len()
+        ));
+        Text::new(&buf, Point::new(5, 33), style_text)
+            .draw(display)
+            .ok();
+        Text::new(name, Point::new(5, 45), style_text)
+            .draw(display)
+            .ok();
+        Rectangle::new(Point::new(105, 24), Size::new(20, 20))
+            .into_styled(PrimitiveStyle::with_fill(Rgb565::from(Rgb888::new(
+                r, g, b,
+            ))))
+            .draw(display)
+            .ok();
+        Text::new("Dbl tap: abort chart", Point::new(5, 84), style_small)
+            .draw(display)
+            .ok();
+    } else {
+        let prompt = match state.cal_black {
+            None => "Place on BLACK tile",
+            Some(_) => "Place on WHITE tile",
+        };
+        Text::new(prompt, Point::new(5, 45), style_text)
+            .draw(display)
+            .ok();
+    }
+    Text::new("Tap to capture", Point::new(5, 60), style_small)
+        .draw(display)
+        .ok();
+    Text::new("Hold: clear", Point::new(5, 72), style_small)
+        .draw(display)
+        .ok();
+
+    if let Some(msg) = state.cal_message {
+        Text::new(msg, Point::new(5, 100), style_text)
+            .draw(display)
+            .ok();
+    }
+
+    let status = match (state.calibration, state.correction) {
+        (Some(_), Some(_)) => "Calibrated + chart",
+        (Some(_), None) => "Calibrated",
+        (None, _) => "Not calibrated",
+    };
+    Text::new(status, Point::new(5, 124), style_small)
+        .draw(display)
+        .ok();
+}
+
+fn draw_settings_screen<D>(
+    display: &mut D,
+    state: &AppState,
+    style_title: MonoTextStyle<Rgb565>,
+    style_text: MonoTextStyle<Rgb565>,
+    style_small: MonoTextStyle<Rgb565>,
+) where
+    D: DrawTarget<Color = Rgb565>,
+{
+    // "Settings" = 8 chars * 9px = 72px. Center on 130: (130-72)/2 = 29
+    Text::new("Settings", Point::new(29, 12), style_title)
+        .draw(display)
+        .ok();
+
+    use core::fmt::Write;
+    let mut y = 33;
+    for (i, setting) in Setting::ALL.iter().enumerate() {
+        let mut number = heapless::String::<8>::new();
+        let value = match setting {
+            Setting::Palette => PALETTES[state.palette].name,
+            Setting::Names if state.describe => "descriptive",
+            Setting::Names => "palette",
+            Setting::Formula => state.formula.label(),
+            Setting::MaxDistance => {
+                // In dE00; other formulas are scaled to match
+                let limit = MAX_DISTANCES[state.max_distance];
+                let _ = number.write_fmt(format_args!("{:.0}", limit));
+                number.as_str()
+            }
+            Setting::Adaptation => state.cat.label(),
+            Setting::Values => state.readout.label(),
+        };
+        // Marker, 7-char label column, then up to 11 chars of value
+        let mut buf = heapless::String::<32>::new();
+        let marker = if i == state.setting { '>' } else { ' ' };
+        let _ = buf.write_fmt(format_args!("{}{:<8}{}", marker, setting.label(), value));
+        Text::new(&buf, Point::new(5, y), style_text)
+            .draw(display)
+            .ok();
+        y += 12;
+    }
+
+    Text::new("Tap: next row", Point::new(5, 112), style_small)
+        .draw(display)
+        .ok();
+    Text::new("Hold: change", Point::new(5, 124), style_small)
+        .draw(display)
+        .ok();
+}
+
+fn draw_harmony_screen<D>(
+    display: &mut D,
+    state: &AppState,
+    style_title: MonoTextStyle<Rgb565>,
+    style_text: MonoTextStyle<Rgb565>,
+    style_small: MonoTextStyle<Rgb565>,
+) where
+    D: DrawTarget<Color = Rgb565>,
+{
+    // "Harmony" = 7 chars * 9px = 63px. Center on 130: (130-63)/2 = 33
+    Text::new("Harmony", Point::new(33, 12), style_title)
+        .draw(display)
+        .ok();
+
+    let Some(base) = &state.current_reading else {
+        Text::new("Pick a color first", Point::new(10, 60), style_text)
+            .draw(display)
+            .ok();
+        return;
+    };
+
+    Text::new(state.scheme.label(), Point::new(5, 26), style_small)
+        .draw(display)
+        .ok();
+
+    // One row per color: swatch, hex, nearest name in the active palette
+    use core::fmt::Write;
+    let base = Srgb::from_rgb8(base.color.r(), base.color.g(), base.color.b());
+    let mut y = 31;
+    for color in harmony::generate(base, state.scheme) {
+        let (r, g, b) = color.to_rgb8();
+        Rectangle::new(Point::new(5, y), Size::new(14, 14))
+            .into_styled(PrimitiveStyle::with_fill(Rgb565::from(Rgb888::new(
+                r, g, b,
+            ))))
+            .draw(display)
+            .ok();
+
+        let mut buf = heapless::String::<32>::new();
+        let _ = buf.write_fmt(format_args!("#{:02X}{:02X}{:02X}", r, g, b));
+        Text::new(&buf, Point::new(24, y + 10), style_text)
+            .draw(display)
+            .ok();
+
+        let matches: Matches<1> = match_colors(
+            PALETTES[state.palette],
+            r,
+            g,
+            b,
+            state.formula,
+            MAX_DISTANCES[state.max_distance],
+        );
+        let name = matches.best().map_or("-", |m| m.name);
+        Text::new(name, Point::new(70, y + 10), style_small)
+            .draw(display)
+            .ok();
+        y += 18;
+    }
+
+    Text::new("Tap: next scheme", Point::new(5, 126), style_small)
+        .draw(display)
+        .ok();
+}
+
+fn draw_contrast_screen<D>(
+    display: &mut D,
+    state: &AppState,
+    style_title: MonoTextStyle<Rgb565>,
+    style_text: MonoTextStyle<Rgb565>,
+    style_small: MonoTextStyle<Rgb565>,
+) where
+    D: DrawTarget<Color = Rgb565>,
+{
+    // "Contrast" = 8 chars * 9px = 72px. Center on 130: (130-72)/2 = 29
+    Text::new("Contrast", Point::new(29, 12), style_title)
+        .draw(display)
+        .ok();
+
+    use core::fmt::Write;
+    let mut buf = heapless::String::<32>::new();
+
+    if let (Some(fg), Some(bg)) = (&state.contrast_fg, &state.contrast_bg) {
+        let c = Contrast::between(fg, bg);
+
+        // Sample text in the picked colors
+        Rectangle::new(Point::new(7, 18), Size::new(115, 30))
+            .into_styled(PrimitiveStyle::with_fill(Rgb565::from(bg.color)))
+            .draw(display)
+            .ok();
+        let sample_style = MonoTextStyle::new(&FONT_9X15, Rgb565::from(fg.color));
+        Text::new("Aa Text", Point::new(33, 38), sample_style)
+            .draw(display)
+            .ok();
+
+        let _ = buf.write_fmt(format_args!("Ratio {:.2}:1", c.ratio));
+        Text::new(&buf, Point::new(5, 62), style_text)
+            .draw(display)
+            .ok();
+
+        // Pass/fail grid: rows are text sizes, columns are levels
+        let pass = MonoTextStyle::new(&FONT_6X10, Rgb565::GREEN);
+        let fail = MonoTextStyle::new(&FONT_6X10, Rgb565::RED);
+        let cell = |ok: bool| if ok { ("PASS", pass) } else { ("FAIL", fail) };
+        Text::new("AA", Point::new(56, 74), style_small)
+            .draw(display)
+            .ok();
+        Text::new("AAA", Point::new(92, 74), style_small)
+            .draw(display)
+            .ok();
+        let rows = [
+            ("Normal", c.aa_normal(), c.aaa_normal(), 86),
+            ("Large", c.aa_large(), c.aaa_large(), 98),
+        ];
+        for (label, aa, aaa, y) in rows {
+            Text::new(label, Point::new(5, y), style_text)
+                .draw(display)
+                .ok();
+            let (text, style) = cell(aa);
+            Text::new(text, Point::new(50, y), style).draw(display).ok();
+            let (text, style) = cell(aaa);
+            Text::new(text, Point::new(89, y), style).draw(display).ok();
+        }
+
+        buf.clear();
+        let _ = buf.write_fmt(format_args!("APCA Lc {:.1}", c.apca));
+        Text::new(&buf, Point::new(5, 112), style_text)
+            .draw(display)
+            .ok();
+        Text::new("Tap: start over", Point::new(5, 124), style_small)
+            .draw(display)
+            .ok();
+        return;
+    }
+
+    let Some(color) = &state.history[state.contrast_cursor] else {
+        Text::new("Save colors first", Point::new(10, 60), style_text)
+            .draw(display)
+            .ok();
+        return;
+    };
+
+    let prompt = match state.contrast_fg {
+        None => "Pick text color",
+        Some(_) => "Pick background",
+    };
+    Text::new(prompt, Point::new(5, 28), style_text)
+        .draw(display)
+        .ok();
+
+    // Saved color under the cursor
+    Rectangle::new(Point::new(7, 34), Size::new(115, 40))
+        .into_styled(PrimitiveStyle::with_fill(Rgb565::from(color.color)))
+        .draw(display)
+        .ok();
+    let _ = buf.write_fmt(format_args!("{}. {}", state.contrast_cursor + 1, color));
+    Text::new(&buf, Point::new(5, 86), style_text)
+        .draw(display)
+        .ok();
+
+    if let Some(fg) = &state.contrast_fg {
+        buf.clear();
+        let _ = buf.write_fmt(format_args!("Text: {}", fg));
+        Text::new(&buf, Point::new(5, 100), style_small)
+            .draw(display)
+            .ok();
+    }
+
+    Text::new("Tap: next Hold: pick", Point::new(5, 124), style_small)
+        .draw(display)
+        .ok();
+}
+
+fn draw_mix_screen<D>(
+    display: &mut D,
+    state: &AppState,
+    style_title: MonoTextStyle<Rgb565>,
+    style_text: MonoTextStyle<Rgb565>,
+    style_small: MonoTextStyle<Rgb565>,
+) where
+    D: DrawTarget<Color = Rgb565>,
+{
+    // "Mix" = 3 chars * 9px = 27px. Center on 130: (130-27)/2 = 51
+    Text::new("Mix", Point::new(51, 12), style_title)
+        .draw(display)
+        .ok();
+
+    let Some(target) = &state.current_reading else {
+        Text::new("Pick a color first", Point::new(10, 60), style_text)
+            .draw(display)
+            .ok();
+        return;
+    };
+
+    let Some(recipe) = &state.mix_recipe else {
+        Text::new("Save paints first", Point::new(10, 60), style_text)
+            .draw(display)
+            .ok();
+        return;
+    };
+
+    // Target and predicted mix side by side
+    for (x, color, label) in [(7, target.color, "Target"), (67, recipe.color, "Mix")] {
+        Rectangle::new(Point::new(x, 18), Size::new(55, 30))
+            .into_styled(PrimitiveStyle::with_fill(Rgb565::from(color)))
+            .draw(display)
+            .ok();
+        Text::new(label, Point::new(x, 58), style_small)
+            .draw(display)
+            .ok();
+    }
+
+    // Largest share first, as it would be poured
+    use core::fmt::Write;
+    let mut parts = recipe.parts.clone();
+    parts.sort_unstable_by(|a, b| b.parts.cmp(&a.parts));
+    let mut y = 72;
+    for part in &parts {
+        let mut buf = heapless::String::<32>::new();
+        let unit = if part.parts == 1 { "part" } else { "parts" };
+        let _ = buf.write_fmt(format_args!(
+            "{} {} {}",
+            part.parts, unit, state.mix_paints[part.paint]
+        ));
+        Text::new(&buf, Point::new(5, y), style_text)
+            .draw(display)
+            .ok();
+        y += 12;
+    }
+
+    let mut buf = heapless::String::<32>::new();
+    let _ = buf.write_fmt(format_args!(
+        "{} {:.1}",
+        state.formula.label(),
+        recipe.delta_e
+    ));
+    Text::new(&buf, Point::new(5, 110), style_text)
+        .draw(display)
+        .ok();
+    Text::new("Paints: saved colors", Point::new(5, 124), style_small)
+        .draw(display)
+        .ok();
+}
@@ -0,0 +1,45 @@
+//! Calibration kept in the last flash sector, which `memory.x` leaves out of
+//! the program's FLASH region so firmware updates don't overwrite it.
+
+use embassy_rp::flash::{Blocking, Error, Flash, ERASE_SIZE};
+use embassy_rp::peripherals::FLASH;
+
+use colorpicky_core::calibration::{Calibration, CalibrationError};
+
+/// Flash size assumed by `memory.x` (2 MiB, safe for every RP2350 board).
+pub const FLASH_SIZE: usize = 2 * 1024 * 1024;
+
+/// Offset of the calibration sector from the start of flash.
+const CALIBRATION_OFFSET: u32 = (FLASH_SIZE - ERASE_SIZE) as u32;
+
+pub struct Storage<'d> {
+    flash: Flash<'d, FLASH, Blocking, FLASH_SIZE>,
+}
+
+impl<'d> Storage<'d> {
+    pub fn new(flash: Flash<'d, FLASH, Blocking, FLASH_SIZE>) -> Self {
+        Self { flash }
+    }
+
+    /// The saved calibration. An erased sector (nothing saved yet) reads
+    /// back as `Corrupt`, like any other unrecognized contents.
+    pub fn load_calibration(&mut self) -> Result<Calibration, CalibrationError> {
+        let mut bytes = [0u8; Calibration::SIZE];
+        self.flash
+            .blocking_read(CALIBRATION_OFFSET, &mut bytes)
+            .map_err(|_| CalibrationError::Corrupt)?;
+        Calibration::from_bytes(&bytes)
+    }
+
+    /// Replace the saved calibration; `None` just erases it.
+    pub fn save_calibration(&mut self, calibration: Option<&Calibration>) -> Result<(), Error> {
+        self.flash
+            .blocking_erase(CALIBRATION_OFFSET, CALIBRATION_OFFSET + ERASE_SIZE as u32)?;
+        match calibration {
+            Some(cal) => self
+                .flash
+                .blocking_write(CALIBRATION_OFFSET, &cal.to_bytes()),
+            None => Ok(()),
+        }
+    }
+}
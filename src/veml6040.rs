@@ -0,0 +1,163 @@
+//! Async driver for the Vishay VEML6040 RGBW sensor.
+//!
+//! The VEML6040 has no gain stage and no ID register. Each read forces a
+//! single measurement (AF + TRIG) and waits it out, so samples are always
+//! fresh. Its white channel stands in for the TCS34725 clear channel.
+
+use embassy_time::Timer;
+use embedded_hal_async::i2c::I2c;
+
+use colorpicky_core::sensor::{ColorSensor, Error, ExposureStatus, Metadata, Reading, SensorKind};
+
+pub const ADDRESS: u8 = 0x10;
+
+pub const REG_CONF: u8 = 0x00;
+pub const REG_R_DATA: u8 = 0x08;
+pub const REG_G_DATA: u8 = 0x09;
+pub const REG_B_DATA: u8 = 0x0A;
+pub const REG_W_DATA: u8 = 0x0B;
+
+pub const MASK_CONF_SD: u16 = 0x01; // Shut down
+pub const MASK_CONF_AF: u16 = 0x02; // Forced (one-shot) mode
+pub const MASK_CONF_TRIG: u16 = 0x04; // Start a one-shot measurement
+
+/// No change interrupt, so the app re-reads on this interval.
+const POLL_INTERVAL_MS: u64 = 100;
+
+/// Lux per green count at 40 ms; halves with each doubling of integration time.
+const G_SENSITIVITY_40MS: f32 = 0.25168;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IntegrationTime {
+    Ms40,
+    Ms80,
+    #[default]
+    Ms160,
+    Ms320,
+    Ms640,
+    Ms1280,
+}
+
+impl IntegrationTime {
+    /// IT field value (CONF bits 6:4, unshifted).
+    pub const fn bits(self) -> u16 {
+        self as u16
+    }
+
+    pub const fn millis(self) -> u32 {
+        40 << self.bits()
+    }
+
+    /// Lux per green count at this integration time.
+    pub fn g_sensitivity(self) -> f32 {
+        G_SENSITIVITY_40MS / (1u32 << self.bits()) as f32
+    }
+}
+
+pub struct Veml6040<I2C> {
+    i2c: I2C,
+    integration_time: IntegrationTime,
+    shutdown: bool,
+}
+
+impl<I2C, E> Veml6040<I2C>
+where
+    I2C: I2c<Error = E>,
+{
+    pub fn new(i2c: I2C) -> Self {
+        Self {
+            i2c,
+            integration_time: IntegrationTime::default(),
+            shutdown: false,
+        }
+    }
+
+    pub async fn set_integration_time(
+        &mut self,
+        integration_time: IntegrationTime,
+    ) -> Result<(), Error<E>> {
+        self.integration_time = integration_time;
+        self.write_conf(0).await
+    }
+
+    pub fn integration_time(&self) -> IntegrationTime {
+        self.integration_time
+    }
+
+    async fn write_conf(&mut self, extra: u16) -> Result<(), Error<E>> {
+        let mut conf = (self.integration_time.bits() << 4) | MASK_CONF_AF | extra;
+        if self.shutdown {
+            conf |= MASK_CONF_SD;
+        }
+        let [lsb, msb] = conf.to_le_bytes();
+        self.i2c
+            .write(ADDRESS, &[REG_CONF, lsb, msb])
+            .await
+            .map_err(Error::Bus)
+    }
+
+    async fn read_channel(&mut self, reg: u8) -> Result<u16, Error<E>> {
+        let mut buf = [0u8; 2];
+        self.i2c
+            .write_read(ADDRESS, &[reg], &mut buf)
+            .await
+            .map_err(Error::Bus)?;
+        Ok(u16::from_le_bytes(buf))
+    }
+}
+
+impl<I2C, E> ColorSensor for Veml6040<I2C>
+where
+    I2C: I2c<Error = E>,
+{
+    type BusError = E;
+
+    async fn init(&mut self) -> Result<(), Error<E>> {
+        self.shutdown = false;
+        self.write_conf(0).await
+    }
+
+    async fn read(&mut self) -> Result<Reading, Error<E>> {
+        self.write_conf(MASK_CONF_TRIG).await?;
+        // The datasheet gives no ready flag; allow 10% for oscillator tolerance
+        let ms = self.integration_time.millis();
+        Timer::after_millis((ms + ms / 10) as u64).await;
+
+        let r = self.read_channel(REG_R_DATA).await?;
+        let g = self.read_channel(REG_G_DATA).await?;
+        let b = self.read_channel(REG_B_DATA).await?;
+        let w = self.read_channel(REG_W_DATA).await?;
+
+        Ok(Reading {
+            r,
+            g,
+            b,
+            c: w,
+            meta: Metadata {
+                sensor: SensorKind::Veml6040,
+                integration_us: ms * 1000,
+                gain: 1,
+                full_scale: u16::MAX,
+                status: ExposureStatus::classify(w, u16::MAX),
+                ambient: None,
+            },
+            lux: Some(g as f32 * self.integration_time.g_sensitivity()),
+            cct: None,
+        })
+    }
+
+    async fn sleep(&mut self) -> Result<(), Error<E>> {
+        self.shutdown = true;
+        self.write_conf(0).await
+    }
+
+    async fn wake(&mut self) -> Result<(), Error<E>> {
+        self.shutdown = false;
+        self.write_conf(0).await
+    }
+
+    async fn wait_for_change(&mut self) -> Result<(), Error<E>> {
+        Timer::after_millis(POLL_INTERVAL_MS).await;
+        Ok(())
+    }
+}